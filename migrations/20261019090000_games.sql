@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS games (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    start_distance INTEGER NOT NULL,
+    distance INTEGER NOT NULL,
+    moves INTEGER NOT NULL DEFAULT 0
+);
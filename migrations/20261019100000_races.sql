@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS races (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    seed INTEGER NOT NULL,
+    start_board TEXT NOT NULL,
+    distance INTEGER NOT NULL,
+    winner INTEGER,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS race_players (
+    race_id INTEGER NOT NULL REFERENCES races (id),
+    seat INTEGER NOT NULL,
+    session TEXT NOT NULL,
+    board TEXT NOT NULL,
+    moves INTEGER NOT NULL DEFAULT 0,
+    finished_at TEXT,
+    PRIMARY KEY (race_id, seat),
+    UNIQUE (race_id, session)
+);
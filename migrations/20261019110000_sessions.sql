@@ -0,0 +1,3 @@
+-- every visitor gets their own board and game instead of sharing one
+ALTER TABLE full_path ADD COLUMN session TEXT NOT NULL DEFAULT '';
+ALTER TABLE games ADD COLUMN session TEXT NOT NULL DEFAULT '';
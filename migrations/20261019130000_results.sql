@@ -0,0 +1,20 @@
+-- the clock and the board's shape for every game, and a row per solved game
+ALTER TABLE games ADD COLUMN rows INTEGER NOT NULL DEFAULT 3;
+ALTER TABLE games ADD COLUMN cols INTEGER NOT NULL DEFAULT 3;
+ALTER TABLE games ADD COLUMN started_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN solved_ms INTEGER;
+
+CREATE TABLE IF NOT EXISTS results (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session TEXT NOT NULL,
+    game_id INTEGER NOT NULL,
+    rows INTEGER NOT NULL,
+    cols INTEGER NOT NULL,
+    difficulty TEXT NOT NULL,
+    optimal INTEGER NOT NULL,
+    moves INTEGER NOT NULL,
+    elapsed_ms INTEGER NOT NULL,
+    solved_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS results_session ON results (session, rows, cols, difficulty);
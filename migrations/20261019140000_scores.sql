@@ -0,0 +1,23 @@
+-- where every game started and the tiles slid since, so a score can be replayed
+ALTER TABLE games ADD COLUMN start_board TEXT NOT NULL DEFAULT '';
+ALTER TABLE games ADD COLUMN history TEXT NOT NULL DEFAULT '';
+
+-- leaderboard entries, one per solved game at most
+CREATE TABLE IF NOT EXISTS scores (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session TEXT NOT NULL,
+    game_id INTEGER NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    rows INTEGER NOT NULL,
+    cols INTEGER NOT NULL,
+    difficulty TEXT NOT NULL,
+    optimal INTEGER NOT NULL,
+    moves INTEGER NOT NULL,
+    elapsed_ms INTEGER NOT NULL,
+    efficiency INTEGER NOT NULL,
+    start_board TEXT NOT NULL,
+    history TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS scores_board ON scores (rows, cols, difficulty, created_at);
@@ -0,0 +1,17 @@
+-- the puzzle of the day a game, result or score belongs to, NULL for everything else
+ALTER TABLE games ADD COLUMN daily TEXT;
+ALTER TABLE games ADD COLUMN solved_moves INTEGER;
+ALTER TABLE results ADD COLUMN daily TEXT;
+ALTER TABLE scores ADD COLUMN daily TEXT;
+
+-- one go at each day's leaderboard
+CREATE UNIQUE INDEX IF NOT EXISTS scores_daily ON scores (daily, session) WHERE daily IS NOT NULL;
+
+-- when each session first started each day's puzzle. starting it again later
+-- carries on that clock rather than a new one
+CREATE TABLE IF NOT EXISTS daily_starts (
+    session TEXT NOT NULL,
+    daily TEXT NOT NULL,
+    started_ms INTEGER NOT NULL,
+    PRIMARY KEY (session, daily)
+);
@@ -0,0 +1,5 @@
+-- how pushes of several tiles were counted, single_tile or multi_tile. moves
+-- are only compared between games counted the same way
+ALTER TABLE games ADD COLUMN metric TEXT NOT NULL DEFAULT 'single_tile';
+ALTER TABLE results ADD COLUMN metric TEXT NOT NULL DEFAULT 'single_tile';
+ALTER TABLE scores ADD COLUMN metric TEXT NOT NULL DEFAULT 'single_tile';
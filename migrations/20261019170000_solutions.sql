@@ -0,0 +1,22 @@
+-- solutions used to go into full_path next to the board being played, and their
+-- first step's iteration_index 0 clashed with it. they're kept apart now and
+-- full_path is back to one row per session, the board as it stands
+CREATE TABLE IF NOT EXISTS solutions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session TEXT NOT NULL,
+    game_id INTEGER NOT NULL,
+    -- how many moves into the game it was solved from, 0 is the scramble itself
+    from_move INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS solution_steps (
+    solution_id INTEGER NOT NULL REFERENCES solutions (id) ON DELETE CASCADE,
+    step INTEGER NOT NULL,
+    board TEXT NOT NULL,
+    PRIMARY KEY (solution_id, step)
+);
+
+DELETE FROM full_path
+WHERE iteration_index != 0
+   OR id NOT IN (SELECT MIN(id) FROM full_path WHERE iteration_index = 0 GROUP BY session);
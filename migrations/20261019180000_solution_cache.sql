@@ -0,0 +1,18 @@
+-- solved boards anyone can reuse, a board's code already says its shape so the
+-- goal is that shape's solved board. path is the step boards as codes, space
+-- separated, the board itself first
+CREATE TABLE IF NOT EXISTS solution_cache (
+    board TEXT NOT NULL,
+    goal TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    path TEXT NOT NULL,
+    algorithm TEXT NOT NULL,
+    optimal BOOLEAN NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 0,
+    -- the least recently used go first once there are too many
+    used_ms INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (board, goal, metric)
+);
+
+CREATE INDEX IF NOT EXISTS solution_cache_used ON solution_cache (used_ms);
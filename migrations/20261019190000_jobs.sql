@@ -0,0 +1,19 @@
+-- solves too long for a request, run by the workers in the background. board is
+-- a board code, path the step boards as codes, space separated, once it's done.
+-- nodes is how many boards the search has looked at so far
+CREATE TABLE IF NOT EXISTS jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session TEXT NOT NULL,
+    board TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    budget INTEGER NOT NULL,
+    -- queued, running, done or gave_up
+    status TEXT NOT NULL DEFAULT 'queued',
+    nodes INTEGER NOT NULL DEFAULT 0,
+    path TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TEXT,
+    finished_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS jobs_status ON jobs (status, id);
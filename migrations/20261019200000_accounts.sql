@@ -0,0 +1,18 @@
+-- optional accounts. a browser that logs in plays as the session "user-<id>"
+-- from then on, so everything keyed by session works for accounts as it is
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    -- argon2, as a PHC string with its salt and parameters
+    password_hash TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- a row for every browser logged in, keyed by a hash of the account cookie's
+-- token rather than the token. a login stops working at expires_ms
+CREATE TABLE IF NOT EXISTS logins (
+    token TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    expires_ms INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- what a replay plays back, results from before this can't be replayed
+ALTER TABLE results ADD COLUMN start_board TEXT NOT NULL DEFAULT '';
+ALTER TABLE results ADD COLUMN history TEXT NOT NULL DEFAULT '';
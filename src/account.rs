@@ -0,0 +1,297 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use askama::Template;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::{Extension, Form};
+use axum_extra::extract::cookie::CookieJar;
+use rand::distributions::{Alphanumeric, DistString};
+use serde::Deserialize;
+
+use crate::bests::{self, Best};
+use crate::now_ms;
+use crate::session::{self, Account, Session, Sessions, ACCOUNT_COOKIE, LOGIN_MS};
+use crate::store::Store;
+
+const SHOWN: usize = 20;
+const NAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=20;
+const MIN_PASSWORD: usize = 8;
+// checked against when there's no one by the name given, so a missing name
+// takes as long to turn down as a wrong password. argon2's default parameters,
+// the same as hash uses
+const NO_ONE: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$d+LsQ01kEqHQEKGxgcsMqw$HWoWU6GG/jimWWHDl+j2YrvH9q6AMqn1601/EK4gAhQ";
+
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub(crate) struct User {
+    pub(crate) id: i64,
+    pub(crate) name: String,
+    // argon2's PHC string, salt and parameters included
+    pub(crate) password_hash: String,
+    pub(crate) created_at: String,
+}
+
+// a row of results, for the profile and history pages
+#[derive(sqlx::FromRow, Debug)]
+pub struct PastGame {
+    pub id: i64,
+    pub rows: i32,
+    pub cols: i32,
+    pub difficulty: String,
+    pub metric: String,
+    pub optimal: i32,
+    pub moves: i32,
+    pub elapsed_ms: i64,
+    pub daily: Option<String>,
+    pub solved_at: String,
+    // what history::replay plays back, empty for games from before it was kept
+    pub start_board: String,
+    // tile indexes slid, space separated like Game::history
+    pub history: String,
+}
+
+impl PastGame {
+    pub fn time(&self) -> String {
+        bests::clock(self.elapsed_ms)
+    }
+
+    pub fn counted(&self) -> &'static str {
+        bests::counted(&self.metric)
+    }
+
+    // games solved before their moves were kept can't be played back
+    pub fn replayable(&self) -> bool {
+        !self.start_board.is_empty()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Credentials {
+    name: String,
+    password: String,
+    // the checkbox, there when it's ticked. what the browser's played so far
+    // goes over to the account
+    #[serde(default)]
+    keep: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "account.html")]
+struct AccountTemplate {
+    title: String,
+    // "/login" or "/register", where the form goes
+    action: &'static str,
+    name: String,
+    error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "profile.html")]
+struct ProfileTemplate {
+    title: String,
+    name: String,
+    created_at: String,
+    // the account's own page, it gets the log out button
+    own: bool,
+    solved: i32,
+    bests: Vec<Best>,
+    games: Vec<PastGame>,
+}
+
+fn form(action: &'static str, name: String, error: Option<String>) -> Response {
+    let status = match error {
+        Some(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        None => StatusCode::OK,
+    };
+    let title = match action {
+        "/register" => String::from("sign up"),
+        _ => String::from("log in"),
+    };
+    let template = AccountTemplate {
+        title,
+        action,
+        name,
+        error,
+    };
+    (status, template).into_response()
+}
+
+pub async fn register_page() -> Response {
+    form("/register", String::new(), None)
+}
+
+pub async fn login_page() -> Response {
+    form("/login", String::new(), None)
+}
+
+fn check(credentials: &Credentials) -> Result<(), String> {
+    let name = &credentials.name;
+    if !NAME_LENGTH.contains(&name.chars().count()) {
+        return Err(format!(
+            "a name is {} to {} characters",
+            NAME_LENGTH.start(),
+            NAME_LENGTH.end()
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(String::from(
+            "a name is letters, digits, _ and - and nothing else",
+        ));
+    }
+    if credentials.password.chars().count() < MIN_PASSWORD {
+        return Err(format!(
+            "a password is {} characters at least",
+            MIN_PASSWORD
+        ));
+    }
+    Ok(())
+}
+
+// argon2 takes a while on purpose, off the runtime's threads
+async fn hash(password: String) -> String {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut rand::rngs::OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .unwrap()
+            .to_string()
+    })
+    .await
+    .unwrap()
+}
+
+async fn verify(password: String, hash: String) -> bool {
+    tokio::task::spawn_blocking(move || {
+        let Ok(hash) = PasswordHash::new(&hash) else {
+            return false;
+        };
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+    .await
+    .unwrap()
+}
+
+// a new account, logged straight into
+pub async fn register(
+    State(sessions): State<Sessions>,
+    Extension(session): Extension<Session>,
+    account: Option<Extension<Account>>,
+    jar: CookieJar,
+    Form(credentials): Form<Credentials>,
+) -> Response {
+    if let Err(error) = check(&credentials) {
+        return form("/register", credentials.name, Some(error));
+    }
+    let hash = hash(credentials.password).await;
+    let Some(id) = sessions.store.add_user(&credentials.name, &hash).await else {
+        let error = format!("{} is taken", credentials.name);
+        return form("/register", credentials.name, Some(error));
+    };
+    tracing::info!(user = id, name = credentials.name, "signed up");
+
+    let keep = credentials.keep.is_some() && account.is_none();
+    log_in(&sessions, &session, keep, jar, id, &credentials.name).await
+}
+
+pub async fn login(
+    State(sessions): State<Sessions>,
+    Extension(session): Extension<Session>,
+    account: Option<Extension<Account>>,
+    jar: CookieJar,
+    Form(credentials): Form<Credentials>,
+) -> Response {
+    // the same answer whether it's the name or the password that's wrong, and
+    // just as slow
+    let user = sessions.store.user(&credentials.name).await;
+    let hash = user
+        .as_ref()
+        .map_or(NO_ONE, |user| user.password_hash.as_str())
+        .to_owned();
+    let verified = verify(credentials.password, hash).await;
+    let user = match user {
+        Some(user) if verified => user,
+        _ => {
+            let error = String::from("that name and password don't go together");
+            return form("/login", credentials.name, Some(error));
+        }
+    };
+
+    let keep = credentials.keep.is_some() && account.is_none();
+    log_in(&sessions, &session, keep, jar, user.id, &user.name).await
+}
+
+// hands out the account cookie, with what the guest played moved over first
+// when they asked for it
+async fn log_in(
+    sessions: &Sessions,
+    guest: &Session,
+    keep: bool,
+    jar: CookieJar,
+    user_id: i64,
+    name: &str,
+) -> Response {
+    if keep {
+        let account = Session::of_user(user_id);
+        sessions.store.merge_sessions(guest, &account).await;
+    }
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let token_hash = session::token_hash(&token);
+    sessions
+        .store
+        .add_login(&token_hash, user_id, now_ms() + LOGIN_MS)
+        .await;
+
+    let jar = jar.add(sessions.cookie(ACCOUNT_COOKIE, token));
+    (jar, Redirect::to(&format!("/u/{}", name))).into_response()
+}
+
+// the login's gone from the store too, so a copied cookie stops working. the
+// browser's back to playing as the guest it was before
+pub async fn logout(State(sessions): State<Sessions>, jar: CookieJar) -> Response {
+    if let Some(token) = jar.get(ACCOUNT_COOKIE) {
+        sessions
+            .store
+            .remove_login(&session::token_hash(token.value()))
+            .await;
+    }
+    let jar = jar.remove(sessions.cookie(ACCOUNT_COOKIE, String::new()));
+    (jar, Redirect::to("/")).into_response()
+}
+
+// the logged in account's page, or the form to log in
+pub async fn own(account: Option<Extension<Account>>) -> Redirect {
+    match account {
+        Some(Extension(account)) => Redirect::to(&format!("/u/{}", account.name)),
+        None => Redirect::to("/login"),
+    }
+}
+
+// anyone can look, it's what they've solved and nothing else
+pub async fn profile(
+    State(store): State<Store>,
+    account: Option<Extension<Account>>,
+    Path(name): Path<String>,
+) -> Response {
+    let Some(user) = store.user(&name).await else {
+        return (StatusCode::NOT_FOUND, format!("no one's called {}", name)).into_response();
+    };
+    let session = Session::of_user(user.id);
+    let bests = store.bests(&session).await;
+
+    ProfileTemplate {
+        title: user.name.clone(),
+        own: account.is_some_and(|Extension(account)| account.id == user.id),
+        solved: bests.iter().map(|best| best.solved).sum(),
+        bests,
+        games: store.past_games(&session, SHOWN).await,
+        name: user.name,
+        created_at: user.created_at,
+    }
+    .into_response()
+}
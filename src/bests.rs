@@ -0,0 +1,72 @@
+use askama::Template;
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::Extension;
+
+use crate::session::Session;
+use crate::slayin::MoveMetric;
+use crate::store::Store;
+
+// fewest moves and quickest time, they don't have to come from the same game
+#[derive(sqlx::FromRow, Debug)]
+pub struct Best {
+    pub rows: i32,
+    pub cols: i32,
+    pub difficulty: String,
+    pub metric: String,
+    pub moves: i32,
+    pub elapsed_ms: i64,
+    pub solved: i32,
+}
+
+impl Best {
+    pub fn time(&self) -> String {
+        clock(self.elapsed_ms)
+    }
+
+    pub fn counted(&self) -> &'static str {
+        counted(&self.metric)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "bests.html")]
+struct BestsTemplate {
+    title: String,
+    bests: Vec<Best>,
+}
+
+pub async fn page(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+) -> impl IntoResponse {
+    let bests = store.bests(&session).await;
+
+    BestsTemplate {
+        title: String::from("personal bests"),
+        bests,
+    }
+}
+
+// how far from solved a game started, bests are only compared between games alike
+pub fn difficulty(start_distance: i32) -> &'static str {
+    match start_distance {
+        ..=9 => "easy",
+        10..=19 => "medium",
+        _ => "hard",
+    }
+}
+
+// moves counted a tile at a time or a push at a time, see slayin::MoveMetric
+pub fn counted(metric: &str) -> &'static str {
+    match MoveMetric::parse(metric).unwrap_or_default() {
+        MoveMetric::SingleTile => "per tile",
+        MoveMetric::MultiTile => "per push",
+    }
+}
+
+// 83000 -> "1:23"
+pub fn clock(ms: i64) -> String {
+    let seconds = ms / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,171 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::Json;
+use lru::LruCache;
+use serde::Serialize;
+
+use crate::slayin::{Board, MoveMetric};
+use crate::solver_task;
+use crate::store::Store;
+
+// what's in the algorithm column, the only solver there is
+pub(crate) const A_STAR: &str = "a_star";
+
+// a solve is the same for anyone whose board has the same code, goal and count
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct PathKey {
+    pub(crate) board: String,
+    pub(crate) goal: String,
+    pub(crate) metric: MoveMetric,
+}
+
+impl PathKey {
+    pub(crate) fn new(board: &Board, metric: MoveMetric) -> PathKey {
+        let goal = Board::with_size(Board::goal(board.rows, board.cols), board.rows, board.cols);
+        PathKey {
+            board: board.code(),
+            goal: goal.code(),
+            metric,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CachedPath {
+    pub(crate) key: PathKey,
+    // the board itself first, the goal last
+    pub(crate) steps: Vec<Board>,
+    pub(crate) algorithm: String,
+    // a* with a heuristic that never overestimates, so true for now
+    pub(crate) optimal: bool,
+}
+
+// counted since the server started, /api/solver/cache
+#[derive(Serialize, Debug)]
+pub struct CacheStats {
+    pub(crate) entries: usize,
+    pub(crate) memory_hits: u64,
+    pub(crate) store_hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) memory_evictions: u64,
+    pub(crate) store_evictions: u64,
+}
+
+// solves anyone's already asked for, the last few in memory and the rest in the
+// store. a board that's been solved once comes back without running the solver
+#[derive(Clone)]
+pub(crate) struct SolutionCache(Arc<Cache>);
+
+struct Cache {
+    recent: Mutex<LruCache<PathKey, Vec<Board>>>,
+    store: Store,
+    // rows the store holds on to before the least recently used go
+    keep: usize,
+    memory_hits: AtomicU64,
+    store_hits: AtomicU64,
+    misses: AtomicU64,
+    memory_evictions: AtomicU64,
+    store_evictions: AtomicU64,
+}
+
+impl SolutionCache {
+    pub(crate) fn new(store: Store, size: NonZeroUsize, keep: usize) -> SolutionCache {
+        SolutionCache(Arc::new(Cache {
+            recent: Mutex::new(LruCache::new(size)),
+            store,
+            keep,
+            memory_hits: AtomicU64::new(0),
+            store_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            memory_evictions: AtomicU64::new(0),
+            store_evictions: AtomicU64::new(0),
+        }))
+    }
+
+    // the way home from board, None if the solver ran out of budget. those
+    // aren't kept, a bigger budget might get there next time
+    pub(crate) async fn solve(&self, board: &Board, metric: MoveMetric) -> Option<Vec<Board>> {
+        self.solve_reporting(board, metric, |_| {}).await
+    }
+
+    // solve, with progress hearing how many boards the solver's looked at every
+    // so often. nothing when the path's cached
+    pub(crate) async fn solve_reporting(
+        &self,
+        board: &Board,
+        metric: MoveMetric,
+        progress: impl FnMut(usize) + Send + 'static,
+    ) -> Option<Vec<Board>> {
+        let cache = &self.0;
+        let key = PathKey::new(board, metric);
+
+        if let Some(steps) = cache.recent.lock().unwrap().get(&key) {
+            cache.memory_hits.fetch_add(1, Ordering::Relaxed);
+            return Some(steps.clone());
+        }
+        if let Some(cached) = cache.store.cached_path(&key).await {
+            cache.store_hits.fetch_add(1, Ordering::Relaxed);
+            self.remember(key, cached.steps.clone());
+            return Some(cached.steps);
+        }
+
+        cache.misses.fetch_add(1, Ordering::Relaxed);
+        let searched = board.clone();
+        let steps = solver_task(move || searched.solve_reporting(metric, progress)).await?;
+        let steps: Vec<Board> = steps
+            .into_iter()
+            .map(|cells| Board::with_size(cells, board.rows, board.cols))
+            .collect();
+        self.keep(key, steps.clone()).await;
+        Some(steps)
+    }
+
+    // a path solved somewhere else, a background job, for the next solve of
+    // board to find
+    pub(crate) async fn insert(&self, board: &Board, metric: MoveMetric, steps: Vec<Board>) {
+        self.keep(PathKey::new(board, metric), steps).await;
+    }
+
+    async fn keep(&self, key: PathKey, steps: Vec<Board>) {
+        let cache = &self.0;
+        let path = CachedPath {
+            key: key.clone(),
+            steps: steps.clone(),
+            algorithm: String::from(A_STAR),
+            optimal: true,
+        };
+        let evicted = cache.store.cache_path(&path, cache.keep).await;
+        cache
+            .store_evictions
+            .fetch_add(evicted as u64, Ordering::Relaxed);
+        self.remember(key, steps);
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        let cache = &self.0;
+        CacheStats {
+            entries: cache.recent.lock().unwrap().len(),
+            memory_hits: cache.memory_hits.load(Ordering::Relaxed),
+            store_hits: cache.store_hits.load(Ordering::Relaxed),
+            misses: cache.misses.load(Ordering::Relaxed),
+            memory_evictions: cache.memory_evictions.load(Ordering::Relaxed),
+            store_evictions: cache.store_evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn remember(&self, key: PathKey, steps: Vec<Board>) {
+        let pushed_out = self.0.recent.lock().unwrap().push(key.clone(), steps);
+        // push hands back the old value when the key was already there
+        if pushed_out.is_some_and(|(old, _)| old != key) {
+            self.0.memory_evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+pub async fn stats(State(cache): State<SolutionCache>) -> impl IntoResponse {
+    Json(cache.stats())
+}
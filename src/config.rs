@@ -0,0 +1,410 @@
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+
+use crate::{slayin, DB_URL};
+
+// read when it's there, --config / SLIDIN_CONFIG points somewhere else
+const DEFAULT_FILE: &str = "slidin.toml";
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+// everything the server needs to start. each setting comes from the command
+// line, then a SLIDIN_* env var, then the toml file, and falls back to what
+// `cargo run` always used
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub bind: SocketAddr,
+    pub store: Backend,
+    // only opened when the store is sqlite
+    pub database_url: String,
+    // output.css is served out of here
+    pub assets: PathBuf,
+    // uploaded pictures and the tiles cut from them, served as /pictures
+    pub pictures: PathBuf,
+    // see slayin::set_node_budget
+    pub node_budget: usize,
+    // solves kept in memory, and in the store behind that, see cache::SolutionCache
+    pub cache_size: usize,
+    pub cache_keep: usize,
+    // background solves, see jobs::Jobs. job_budget is the most one gets
+    pub workers: usize,
+    pub job_budget: usize,
+    // requests a minute to the routes that solve, see limit::Limits
+    pub per_ip: usize,
+    pub per_session: usize,
+    // solving requests running at once, past it they get a 429
+    pub max_solves: usize,
+    // biggest board, in tiles, anything bigger gets a 413
+    pub max_tiles: usize,
+    // bytes in a request body, picture uploads have their own
+    pub max_body: usize,
+    // account and session cookies only go back over https, for a server behind
+    // tls
+    pub secure_cookies: bool,
+    pub log_level: String,
+    pub log_format: LogFormat,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            bind: SocketAddr::from(([127, 0, 0, 1], 8000)),
+            store: Backend::Sqlite,
+            database_url: String::from(DB_URL),
+            assets: PathBuf::from("templates"),
+            pictures: PathBuf::from("pictures"),
+            node_budget: slayin::NODE_BUDGET,
+            cache_size: 256,
+            cache_keep: 10_000,
+            workers: 2,
+            job_budget: 10 * slayin::NODE_BUDGET,
+            per_ip: 600,
+            per_session: 240,
+            max_solves: 8,
+            max_tiles: slayin::MAX_SIDE * slayin::MAX_SIDE,
+            max_body: 64 * 1024,
+            secure_cookies: false,
+            log_level: String::from("info"),
+            log_format: LogFormat::Pretty,
+        }
+    }
+}
+
+// where games, scores and races are kept, see store::GameStore
+#[derive(ValueEnum, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Sqlite,
+    // nothing outlives the process
+    Memory,
+}
+
+// how log lines come out, see logging::init
+#[derive(ValueEnum, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    // for a person at a terminal
+    #[default]
+    Pretty,
+    // one object a line, for whatever collects the logs
+    Json,
+}
+
+// a flag's --help, with the default taken from Config::default so the two
+// can't drift apart
+fn help(what: &str, default: impl Display) -> String {
+    format!("{} [default: {}]", what, default)
+}
+
+// what the flags and the toml file call one of the enums
+fn name(value: impl ValueEnum) -> String {
+    value.to_possible_value().unwrap().get_name().to_owned()
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "serves the slidin puzzle")]
+struct Args {
+    #[arg(
+        long,
+        env = "SLIDIN_CONFIG",
+        help = format!("toml file to read [default: {} if there is one]", DEFAULT_FILE)
+    )]
+    config: Option<PathBuf>,
+    #[arg(
+        long,
+        env = "SLIDIN_BIND",
+        help = help("address to listen on", Config::default().bind)
+    )]
+    bind: Option<SocketAddr>,
+    #[arg(
+        long,
+        env = "SLIDIN_STORE",
+        help = help("sqlite or memory", name(Config::default().store))
+    )]
+    store: Option<Backend>,
+    #[arg(
+        long,
+        env = "SLIDIN_DATABASE_URL",
+        help = help("sqlite database", Config::default().database_url)
+    )]
+    database_url: Option<String>,
+    #[arg(
+        long,
+        env = "SLIDIN_ASSETS",
+        help = help("directory with output.css", Config::default().assets.display())
+    )]
+    assets: Option<PathBuf>,
+    #[arg(
+        long,
+        env = "SLIDIN_PICTURES",
+        help = help("directory uploaded pictures are kept in", Config::default().pictures.display())
+    )]
+    pictures: Option<PathBuf>,
+    #[arg(
+        long,
+        env = "SLIDIN_NODE_BUDGET",
+        help = help("boards the solver looks at before giving up", Config::default().node_budget)
+    )]
+    node_budget: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_CACHE_SIZE",
+        help = help("solutions kept in memory", Config::default().cache_size)
+    )]
+    cache_size: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_CACHE_KEEP",
+        help = help("solutions kept in the store", Config::default().cache_keep)
+    )]
+    cache_keep: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_WORKERS",
+        help = help("background solves run at once", Config::default().workers)
+    )]
+    workers: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_JOB_BUDGET",
+        help = help("boards a background solve looks at before giving up", Config::default().job_budget)
+    )]
+    job_budget: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_PER_IP",
+        help = help("requests a minute an address gets to the routes that solve", Config::default().per_ip)
+    )]
+    per_ip: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_PER_SESSION",
+        help = help("requests a minute a session gets to the routes that solve", Config::default().per_session)
+    )]
+    per_session: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_MAX_SOLVES",
+        help = help("solving requests at once before the rest get turned away", Config::default().max_solves)
+    )]
+    max_solves: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_MAX_TILES",
+        help = help("tiles in the biggest board the server takes", Config::default().max_tiles)
+    )]
+    max_tiles: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_MAX_BODY",
+        help = help("bytes in a request body", Config::default().max_body)
+    )]
+    max_body: Option<usize>,
+    #[arg(
+        long,
+        env = "SLIDIN_SECURE_COOKIES",
+        help = "mark cookies secure, for serving over https"
+    )]
+    secure_cookies: bool,
+    #[arg(
+        long,
+        env = "SLIDIN_LOG_LEVEL",
+        help = help("error, warn, info, debug or trace", Config::default().log_level)
+    )]
+    log_level: Option<String>,
+    #[arg(
+        long,
+        env = "SLIDIN_LOG_FORMAT",
+        help = help("pretty or json", name(Config::default().log_format))
+    )]
+    log_format: Option<LogFormat>,
+}
+
+// slidin.toml, anything left out falls through to the defaults
+//
+//     bind = "0.0.0.0:8000"
+//     store = "sqlite"
+//     database_url = "sqlite:///var/lib/slidin/sqlite.db"
+//     assets = "/usr/share/slidin"
+//     pictures = "/var/lib/slidin/pictures"
+//     secure_cookies = true
+//     log_level = "warn"
+//     log_format = "json"
+//
+//     [solver]
+//     node_budget = 1000000
+//     cache_size = 256
+//     cache_keep = 10000
+//     workers = 2
+//     job_budget = 5000000
+//
+//     [limits]
+//     per_ip = 600
+//     per_session = 240
+//     max_solves = 8
+//     max_tiles = 16
+//     max_body = 65536
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct File {
+    bind: Option<SocketAddr>,
+    store: Option<Backend>,
+    database_url: Option<String>,
+    assets: Option<PathBuf>,
+    pictures: Option<PathBuf>,
+    secure_cookies: Option<bool>,
+    log_level: Option<String>,
+    log_format: Option<LogFormat>,
+    solver: Solver,
+    limits: Limits,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct Solver {
+    node_budget: Option<usize>,
+    cache_size: Option<usize>,
+    cache_keep: Option<usize>,
+    workers: Option<usize>,
+    job_budget: Option<usize>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct Limits {
+    per_ip: Option<usize>,
+    per_session: Option<usize>,
+    max_solves: Option<usize>,
+    max_tiles: Option<usize>,
+    max_body: Option<usize>,
+}
+
+impl Config {
+    // the process's own flags and env, --help and bad flags exit from in here
+    pub fn load() -> Result<Config, String> {
+        Self::resolve(Args::parse())
+    }
+
+    // same as load but with the flags passed in, errors come back instead of exiting
+    pub fn from_args<I, T>(args: I) -> Result<Config, String>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args = Args::try_parse_from(args).map_err(|error| error.to_string())?;
+        Self::resolve(args)
+    }
+
+    fn resolve(args: Args) -> Result<Config, String> {
+        let file = match &args.config {
+            // asked for by name, so it has to be there
+            Some(path) => read(path)?,
+            None if Path::new(DEFAULT_FILE).exists() => read(Path::new(DEFAULT_FILE))?,
+            None => File::default(),
+        };
+
+        let default = Config::default();
+        let config = Config {
+            bind: args.bind.or(file.bind).unwrap_or(default.bind),
+            store: args.store.or(file.store).unwrap_or(default.store),
+            database_url: args
+                .database_url
+                .or(file.database_url)
+                .unwrap_or(default.database_url),
+            assets: args.assets.or(file.assets).unwrap_or(default.assets),
+            pictures: args.pictures.or(file.pictures).unwrap_or(default.pictures),
+            node_budget: args
+                .node_budget
+                .or(file.solver.node_budget)
+                .unwrap_or(default.node_budget),
+            cache_size: args
+                .cache_size
+                .or(file.solver.cache_size)
+                .unwrap_or(default.cache_size),
+            cache_keep: args
+                .cache_keep
+                .or(file.solver.cache_keep)
+                .unwrap_or(default.cache_keep),
+            workers: args
+                .workers
+                .or(file.solver.workers)
+                .unwrap_or(default.workers),
+            job_budget: args
+                .job_budget
+                .or(file.solver.job_budget)
+                .unwrap_or(default.job_budget),
+            per_ip: args.per_ip.or(file.limits.per_ip).unwrap_or(default.per_ip),
+            per_session: args
+                .per_session
+                .or(file.limits.per_session)
+                .unwrap_or(default.per_session),
+            max_solves: args
+                .max_solves
+                .or(file.limits.max_solves)
+                .unwrap_or(default.max_solves),
+            max_tiles: args
+                .max_tiles
+                .or(file.limits.max_tiles)
+                .unwrap_or(default.max_tiles),
+            max_body: args
+                .max_body
+                .or(file.limits.max_body)
+                .unwrap_or(default.max_body),
+            secure_cookies: args.secure_cookies
+                || file.secure_cookies.unwrap_or(default.secure_cookies),
+            log_level: args
+                .log_level
+                .or(file.log_level)
+                .unwrap_or(default.log_level),
+            log_format: args
+                .log_format
+                .or(file.log_format)
+                .unwrap_or(default.log_format),
+        };
+
+        if !LOG_LEVELS.contains(&config.log_level.as_str()) {
+            return Err(format!(
+                "log level {:?} isn't one of {}",
+                config.log_level,
+                LOG_LEVELS.join(", ")
+            ));
+        }
+        if config.node_budget == 0 || config.job_budget == 0 {
+            return Err(String::from(
+                "the solver can't do anything with a node budget of 0",
+            ));
+        }
+        if config.cache_size == 0 || config.cache_keep == 0 {
+            return Err(String::from(
+                "the solution cache needs room for at least one solution",
+            ));
+        }
+        if config.per_ip == 0 || config.per_session == 0 || config.max_solves == 0 {
+            return Err(String::from(
+                "the limits have to let at least one request through",
+            ));
+        }
+        let smallest = slayin::MIN_SIDE * slayin::MIN_SIDE;
+        if config.max_tiles < smallest {
+            return Err(format!(
+                "max tiles has to fit the smallest board, {} tiles",
+                smallest
+            ));
+        }
+        if config.workers == 0 {
+            return Err(String::from("background solves need at least one worker"));
+        }
+        Ok(config)
+    }
+}
+
+fn read(path: &Path) -> Result<File, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("can't read {}: {}", path.display(), error))?;
+    toml::from_str(&text).map_err(|error| format!("{} isn't valid: {}", path.display(), error))
+}
@@ -0,0 +1,167 @@
+use std::sync::Mutex;
+
+use askama::Template;
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use serde::Deserialize;
+
+use crate::leaderboard::{self, Entry};
+use crate::picture::{self, Pictures};
+use crate::session::Session;
+use crate::slayin::{Board, MoveMetric};
+use crate::store::{self, Store};
+use crate::{
+    begin_game, bests, board_rows, current_board, find_game, solver_task, Game, SlideTemplate,
+};
+
+// every day is a 3x3 medium so the days are comparable
+const DISTANCES: std::ops::RangeInclusive<usize> = 10..=19;
+
+// the last day's board and distance, see day
+static DAY: Mutex<Option<(String, Board, usize)>> = Mutex::new(None);
+
+#[derive(Template)]
+#[template(path = "daily.html")]
+struct DailyTemplate {
+    title: String,
+    date: String,
+    share: Option<String>,
+    entries: Vec<Entry>,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+pub(crate) struct Solved {
+    pub(crate) moves: i32,
+    pub(crate) optimal: i32,
+    pub(crate) elapsed_ms: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Day {
+    date: Option<String>,
+}
+
+// today's board, picking up where the session left it. one go a day, once it's
+// solved the page is the result and the day's leaderboard
+pub async fn page(
+    State(store): State<Store>,
+    State(pictures): State<Pictures>,
+    Extension(session): Extension<Session>,
+) -> Response {
+    let date = today();
+
+    let game = match find_game(&store, &session).await {
+        Some(game) if game.daily.as_deref() == Some(date.as_str()) => game,
+        _ => {
+            if let Some(solved) = store.daily_result(&session, &date).await {
+                return DailyTemplate {
+                    title: format!("puzzle of the day {}", date),
+                    share: Some(summary(&date, &solved)),
+                    entries: leaderboard::daily(&store, &date).await,
+                    date,
+                }
+                .into_response();
+            }
+            start(&store, &session, &date).await
+        }
+    };
+
+    let board = current_board(&store, &session).await;
+    SlideTemplate {
+        title: format!("puzzle of the day {}", date),
+        path: board_rows(&board),
+        hint: None,
+        feedback: None,
+        best: store.best_before(&session, &game).await,
+        share: share(&game),
+        game,
+        picture: picture::tiles(&pictures, &store, &session, &board).await,
+    }
+    .into_response()
+}
+
+pub async fn leaderboard(State(store): State<Store>, Query(day): Query<Day>) -> Response {
+    let date = match day.date {
+        Some(date) => date,
+        None => today(),
+    };
+
+    DailyTemplate {
+        title: format!("puzzle of the day {}", date),
+        share: None,
+        entries: leaderboard::daily(&store, &date).await,
+        date,
+    }
+    .into_response()
+}
+
+// "slidin puzzle 2026-10-19: 22 moves (optimal 18) in 1:23"
+pub fn share(game: &Game) -> Option<String> {
+    let date = game.daily.as_ref()?;
+    let solved = Solved {
+        moves: game.solved_moves?,
+        optimal: game.start_distance,
+        elapsed_ms: game.elapsed_ms(),
+    };
+    Some(summary(date, &solved))
+}
+
+// the same board for everyone on the same day, the date is the seed
+pub fn board(date: &str) -> Board {
+    let seed = date
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap();
+    Board::seeded_board_within(seed, DISTANCES)
+}
+
+fn summary(date: &str, solved: &Solved) -> String {
+    format!(
+        "slidin puzzle {}: {} moves (optimal {}) in {}",
+        date,
+        solved.moves,
+        solved.optimal,
+        bests::clock(solved.elapsed_ms)
+    )
+}
+
+// utc, same as CURRENT_TIMESTAMP everywhere else
+fn today() -> String {
+    store::now()[..10].to_owned()
+}
+
+// a session that's been at the day's puzzle before picks its clock back up,
+// see GameStore::set_daily
+async fn start(store: &Store, session: &Session, date: &str) -> Game {
+    let (board, distance) = day(date).await;
+    // counted a tile at a time whatever the session's been playing, so
+    // everyone's moves compare
+    let game = begin_game(store, session, &board, distance, MoveMetric::SingleTile).await;
+
+    store.set_daily(game.id, date).await;
+    find_game(store, session).await.unwrap()
+}
+
+// the day's board and how far it is from solved. picking it solves boards until
+// one's far enough, so that's done once a day rather than for every player
+async fn day(date: &str) -> (Board, usize) {
+    if let Some((day, board, distance)) = &*DAY.lock().unwrap() {
+        if day == date {
+            return (board.clone(), *distance);
+        }
+    }
+
+    let date_owned = date.to_owned();
+    let (board, distance) = solver_task(move || {
+        let board = board(&date_owned);
+        // picked to be within the solver's budget
+        let distance = board.optimal_distance().unwrap();
+        (board, distance)
+    })
+    .await;
+    *DAY.lock().unwrap() = Some((date.to_owned(), board.clone(), distance));
+    (board, distance)
+}
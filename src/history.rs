@@ -0,0 +1,281 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use askama::Template;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use futures::StreamExt;
+
+use crate::account::PastGame;
+use crate::cache::SolutionCache;
+use crate::session::Session;
+use crate::slayin::{Board, MoveMetric};
+use crate::store::Store;
+use crate::{parse_board, Playback, Stream};
+
+const SHOWN: usize = 100;
+
+#[derive(Template)]
+#[template(path = "history.html")]
+struct HistoryTemplate {
+    title: String,
+    // "/history" or "/u/<name>/history", each game's replay is under it
+    base: String,
+    games: Vec<PastGame>,
+}
+
+#[derive(Template)]
+#[template(path = "replay.html")]
+struct ReplayPage {
+    title: String,
+    frame: ReplayFrame,
+}
+
+// one step of a replay, the player's board next to where the optimal path had
+// got to after as many moves
+#[derive(Template)]
+#[template(path = "replay_frame.html")]
+struct ReplayFrame {
+    path: Vec<Vec<i32>>,
+    optimal_path: Option<Vec<Vec<i32>>>,
+    step: i32,
+    steps: usize,
+    is_last_step: bool,
+    interval: u64,
+    playing: bool,
+    // sent over the stream, the last one takes the stream's place on the page
+    streamed: bool,
+    // the replay's page, the controls step through it
+    base: String,
+    // counted the way the game counted them, so pushes can be one move
+    moves: usize,
+    optimal_step: usize,
+    optimal: usize,
+}
+
+impl ReplayFrame {
+    fn is_blank(&self, cell: &i32) -> bool {
+        *cell == 0
+    }
+}
+
+// a solved game's boards from the scramble to solved, and the optimal path from
+// the same scramble when the solver could find it
+struct Replay {
+    played: Vec<Board>,
+    // moves made by the time of each board
+    moves: Vec<usize>,
+    optimal: Option<Vec<Board>>,
+}
+
+impl Replay {
+    fn frame(&self, base: String, step: usize, interval: u64, playing: bool) -> ReplayFrame {
+        let shown = step.clamp(1, self.played.len()) - 1;
+        // each step of the optimal path is a move, however they're counted
+        let optimal_step = self
+            .optimal
+            .as_ref()
+            .map_or(0, |optimal| self.moves[shown].min(optimal.len() - 1));
+        ReplayFrame {
+            path: rows(&self.played[shown]),
+            optimal_path: self
+                .optimal
+                .as_ref()
+                .map(|optimal| rows(&optimal[optimal_step])),
+            step: shown as i32 + 1,
+            steps: self.played.len(),
+            is_last_step: shown == self.played.len() - 1,
+            interval: interval.clamp(10, 5000),
+            playing,
+            streamed: false,
+            base,
+            moves: self.moves[shown],
+            optimal_step,
+            optimal: self.optimal.as_ref().map_or(0, |optimal| optimal.len() - 1),
+        }
+    }
+}
+
+fn rows(board: &Board) -> Vec<Vec<i32>> {
+    board
+        .cells
+        .chunks(board.cols)
+        .map(|chunk| chunk.to_vec())
+        .collect()
+}
+
+fn not_found(message: String) -> Response {
+    (StatusCode::NOT_FOUND, message).into_response()
+}
+
+// the session's game id played back, a 404 for anyone else's
+async fn load(
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+    id: i64,
+) -> Result<Replay, Response> {
+    let Some(game) = store.past_game(session, id).await else {
+        return Err(not_found(format!("no game {} here", id)));
+    };
+    if !game.replayable() {
+        let message = String::from("that game was solved before replays were kept");
+        return Err(not_found(message));
+    }
+
+    let metric = MoveMetric::parse(&game.metric).unwrap_or_default();
+    let mut board = parse_board(&game.start_board);
+    let optimal = solutions.solve(&board, metric).await;
+    let (mut played, mut moves) = (vec![board.clone()], vec![0]);
+    for idx in game.history.split_whitespace() {
+        let Some(tiles) = board.slide_line(idx.parse().unwrap()) else {
+            break;
+        };
+        moves.push(moves.last().unwrap() + metric.count(tiles));
+        played.push(board.clone());
+    }
+
+    Ok(Replay {
+        played,
+        moves,
+        optimal,
+    })
+}
+
+// whose games someone's pages are, and the name as they signed up with it.
+// None when there's no one by that name
+async fn user_session(store: &Store, name: &str) -> Option<(Session, String)> {
+    let user = store.user(name).await?;
+    Some((Session::of_user(user.id), user.name))
+}
+
+fn list(base: String, games: Vec<PastGame>) -> Response {
+    HistoryTemplate {
+        title: String::from("past games"),
+        base,
+        games,
+    }
+    .into_response()
+}
+
+// the session's solved games, or the account's once logged in
+pub async fn page(State(store): State<Store>, Extension(session): Extension<Session>) -> Response {
+    let games = store.past_games(&session, SHOWN).await;
+    list(String::from("/history"), games)
+}
+
+pub async fn user_page(State(store): State<Store>, Path(name): Path<String>) -> Response {
+    let Some((session, name)) = user_session(&store, &name).await else {
+        return not_found(format!("no one's called {}", name));
+    };
+    let games = store.past_games(&session, SHOWN).await;
+    list(format!("/u/{}/history", name), games)
+}
+
+async fn render(
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+    id: i64,
+    base: String,
+    query: Playback,
+) -> Response {
+    let replay = match load(store, solutions, session, id).await {
+        Ok(replay) => replay,
+        Err(missing) => return missing,
+    };
+    ReplayPage {
+        title: format!("replay of game {}", id),
+        frame: replay.frame(base, query.step, query.interval, query.playing),
+    }
+    .into_response()
+}
+
+// the same controls as the solution's playback, /history/5?step=3 links
+// straight to the 3rd board
+pub async fn replay(
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    Extension(session): Extension<Session>,
+    Path(id): Path<i64>,
+    Query(query): Query<Playback>,
+) -> Response {
+    let base = format!("/history/{}", id);
+    render(&store, &solutions, &session, id, base, query).await
+}
+
+pub async fn user_replay(
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    Path((name, id)): Path<(String, i64)>,
+    Query(query): Query<Playback>,
+) -> Response {
+    let Some((session, name)) = user_session(&store, &name).await else {
+        return not_found(format!("no one's called {}", name));
+    };
+    let base = format!("/u/{}/history/{}", name, id);
+    render(&store, &solutions, &session, id, base, query).await
+}
+
+// a frame every interval ms from query.from on, like solve_stream
+async fn frames(
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+    id: i64,
+    base: String,
+    query: Stream,
+) -> Response {
+    let replay = match load(store, solutions, session, id).await {
+        Ok(replay) => replay,
+        Err(missing) => return missing,
+    };
+    let interval = query.interval.clamp(10, 5000);
+
+    let frames = (query.from + 1..=replay.played.len())
+        .map(|step| {
+            let frame = replay.frame(base.clone(), step, interval, true);
+            ReplayFrame {
+                streamed: true,
+                ..frame
+            }
+            .render()
+            .unwrap()
+        })
+        .collect::<Vec<String>>();
+
+    let stream = futures::stream::iter(frames).then(move |frame| async move {
+        tokio::time::sleep(Duration::from_millis(interval)).await;
+        Ok::<_, Infallible>(Event::default().event("step").data(frame))
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+pub async fn stream(
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    Extension(session): Extension<Session>,
+    Path(id): Path<i64>,
+    Query(query): Query<Stream>,
+) -> Response {
+    let base = format!("/history/{}", id);
+    frames(&store, &solutions, &session, id, base, query).await
+}
+
+pub async fn user_stream(
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    Path((name, id)): Path<(String, i64)>,
+    Query(query): Query<Stream>,
+) -> Response {
+    let Some((session, name)) = user_session(&store, &name).await else {
+        return not_found(format!("no one's called {}", name));
+    };
+    let base = format!("/u/{}/history/{}", name, id);
+    frames(&store, &solutions, &session, id, base, query).await
+}
@@ -0,0 +1,340 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Notify};
+
+use crate::cache::SolutionCache;
+use crate::limit::Limits;
+use crate::session::Session;
+use crate::slayin::{self, Board, MoveMetric};
+use crate::solver_task;
+use crate::store::Store;
+
+// what's in the status column
+pub(crate) const QUEUED: &str = "queued";
+pub(crate) const RUNNING: &str = "running";
+pub(crate) const DONE: &str = "done";
+// ran out of budget, or the board's past what the solver can do
+pub(crate) const GAVE_UP: &str = "gave_up";
+
+// how long an idle worker waits before looking at the queue again, in case a
+// job went in somewhere it didn't hear about
+const IDLE: Duration = Duration::from_secs(1);
+
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub(crate) struct Job {
+    pub(crate) id: i64,
+    // who submitted it, no one else gets to see it
+    pub(crate) session: String,
+    // a board code
+    pub(crate) board: String,
+    pub(crate) metric: String,
+    pub(crate) budget: i64,
+    pub(crate) status: String,
+    pub(crate) nodes: i64,
+    // codes, space separated, start first. only once it's done
+    pub(crate) path: Option<String>,
+    pub(crate) created_at: String,
+    pub(crate) finished_at: Option<String>,
+}
+
+impl Job {
+    fn finished(&self) -> bool {
+        self.status == DONE || self.status == GAVE_UP
+    }
+
+    fn view(&self) -> JobView {
+        let path: Option<Vec<String>> = self
+            .path
+            .as_ref()
+            .map(|path| path.split_whitespace().map(String::from).collect());
+        JobView {
+            id: self.id,
+            status: self.status.clone(),
+            board: self.board.clone(),
+            metric: self.metric.clone(),
+            budget: self.budget,
+            nodes: self.nodes,
+            moves: path.as_ref().map(|path| path.len() - 1),
+            path,
+            created_at: self.created_at.clone(),
+            finished_at: self.finished_at.clone(),
+        }
+    }
+}
+
+// what polling gets, and every event on the stream
+#[derive(Serialize, Clone, Debug)]
+pub struct JobView {
+    id: i64,
+    status: String,
+    board: String,
+    metric: String,
+    budget: i64,
+    nodes: i64,
+    moves: Option<usize>,
+    path: Option<Vec<String>>,
+    created_at: String,
+    finished_at: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Submission {
+    // a board code, see Board::code
+    board: String,
+    #[serde(default)]
+    metric: Option<String>,
+    // boards to look at before giving up, the most the server allows if left out
+    #[serde(default)]
+    budget: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+struct Rejected {
+    error: String,
+}
+
+// solves queued in the store and the workers taking them off it one at a time.
+// each finished path goes in the solution cache, so playing the board after
+// doesn't solve it again
+#[derive(Clone)]
+pub(crate) struct Jobs(Arc<Queue>);
+
+struct Queue {
+    store: Store,
+    solutions: SolutionCache,
+    // the most a job gets, and what it gets when it doesn't ask
+    budget: usize,
+    // a worker waiting for something to do
+    submitted: Notify,
+    // every change to every job, the event streams pick out theirs
+    updates: broadcast::Sender<Job>,
+}
+
+impl Jobs {
+    // the workers start once whatever a restart cut off is back in the queue
+    pub(crate) fn start(
+        store: Store,
+        solutions: SolutionCache,
+        workers: usize,
+        budget: usize,
+    ) -> Jobs {
+        let jobs = Jobs(Arc::new(Queue {
+            store,
+            solutions,
+            budget,
+            submitted: Notify::new(),
+            updates: broadcast::channel(64).0,
+        }));
+
+        let started = jobs.clone();
+        tokio::spawn(async move {
+            let resumed = started.0.store.requeue_jobs().await;
+            if resumed > 0 {
+                tracing::info!(resumed, "picking solve jobs back up");
+            }
+            for worker in 0..workers {
+                tokio::spawn(started.clone().work(worker));
+            }
+        });
+        jobs
+    }
+
+    async fn work(self, worker: usize) {
+        // stops taking jobs once the server's shutting down
+        while !slayin::stopping() {
+            match self.0.store.claim_job().await {
+                Some(job) => self.run(worker, job).await,
+                None => {
+                    let _ = tokio::time::timeout(IDLE, self.0.submitted.notified()).await;
+                }
+            }
+        }
+    }
+
+    async fn run(&self, worker: usize, job: Job) {
+        let queue = &self.0;
+        tracing::info!(worker, job = job.id, board = %job.board, "solving");
+        let _ = queue.updates.send(job.clone());
+
+        let board = Board::from_code(&job.board).unwrap();
+        let metric = MoveMetric::parse(&job.metric).unwrap();
+        let budget = job.budget as usize;
+        let (store, updates) = (queue.store.clone(), queue.updates.clone());
+        let runtime = tokio::runtime::Handle::current();
+        let searched = board.clone();
+        let mut progress = job.clone();
+        let (steps, nodes) = solver_task(move || {
+            let mut closed = 0;
+            let steps = searched.solve_within(metric, budget, |nodes| {
+                closed = nodes;
+                runtime.block_on(store.job_progress(progress.id, nodes));
+                progress.nodes = nodes as i64;
+                let _ = updates.send(progress.clone());
+            });
+            (steps, closed)
+        })
+        .await;
+
+        // cut off rather than given up on, it goes again after the restart
+        if steps.is_none() && slayin::stopping() {
+            return;
+        }
+        let steps: Option<Vec<Board>> = steps.map(|steps| {
+            steps
+                .into_iter()
+                .map(|cells| Board::with_size(cells, board.rows, board.cols))
+                .collect()
+        });
+        queue
+            .store
+            .finish_job(job.id, nodes, steps.as_deref())
+            .await;
+        if let Some(steps) = steps {
+            queue.solutions.insert(&board, metric, steps).await;
+        }
+
+        let finished = queue.store.job(job.id).await.unwrap();
+        tracing::info!(
+            worker,
+            job = job.id,
+            status = finished.status,
+            nodes,
+            "finished"
+        );
+        let _ = queue.updates.send(finished);
+    }
+}
+
+fn rejected(status: StatusCode, error: String) -> Response {
+    (status, Json(Rejected { error })).into_response()
+}
+
+// 202 with where to find it, the solve happens whenever a worker gets to it
+pub async fn submit(
+    State(store): State<Store>,
+    State(jobs): State<Jobs>,
+    State(limits): State<Limits>,
+    Extension(session): Extension<Session>,
+    Json(submission): Json<Submission>,
+) -> Response {
+    let unprocessable = StatusCode::UNPROCESSABLE_ENTITY;
+    let board = match Board::from_code(&submission.board) {
+        Ok(board) => board,
+        Err(error) => {
+            let error = format!("{} isn't a board code: {}", submission.board, error);
+            return rejected(unprocessable, error);
+        }
+    };
+    if let Err(limited) = limits.check_size(board.rows, board.cols) {
+        return limited.into_response();
+    }
+    let (solvable, explanation) = board.solvability();
+    if !solvable {
+        return rejected(unprocessable, explanation);
+    }
+    let metric = match submission.metric.as_deref() {
+        None => MoveMetric::default(),
+        Some(metric) => match MoveMetric::parse(metric) {
+            Some(metric) => metric,
+            None => return rejected(unprocessable, format!("no such move count {:?}", metric)),
+        },
+    };
+    let budget = match submission.budget {
+        None => jobs.0.budget,
+        Some(0) => {
+            let error = String::from("the solver can't do anything with a budget of 0");
+            return rejected(unprocessable, error);
+        }
+        Some(budget) => budget.min(jobs.0.budget),
+    };
+
+    let id = store.add_job(&session, &board, metric, budget).await;
+    jobs.0.submitted.notify_one();
+    let job = store.job(id).await.unwrap();
+    (
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/api/jobs/{}", id))],
+        Json(job.view()),
+    )
+        .into_response()
+}
+
+// the session's job id, None for anyone else's the same as for one that
+// doesn't exist
+async fn own_job(store: &Store, session: &Session, id: i64) -> Option<Job> {
+    store.job(id).await.filter(|job| job.session == session.0)
+}
+
+pub async fn status(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    Path(id): Path<i64>,
+) -> Response {
+    match own_job(&store, &session, id).await {
+        Some(job) => Json(job.view()).into_response(),
+        None => rejected(StatusCode::NOT_FOUND, format!("no job {}", id)),
+    }
+}
+
+// the job as it is now, then again every time it changes, until it's finished
+pub async fn events(
+    State(store): State<Store>,
+    State(jobs): State<Jobs>,
+    Extension(session): Extension<Session>,
+    Path(id): Path<i64>,
+) -> Response {
+    // listening before looking, so nothing happens in between unheard
+    let updates = jobs.0.updates.subscribe();
+    let Some(job) = own_job(&store, &session, id).await else {
+        return rejected(StatusCode::NOT_FOUND, format!("no job {}", id));
+    };
+
+    let stream = futures::stream::unfold(
+        (Some(job), updates, false),
+        move |(next, mut updates, finished)| {
+            let store = store.clone();
+            async move {
+                if finished {
+                    return None;
+                }
+                let job = match next {
+                    Some(job) => job,
+                    None => next_update(&store, &mut updates, id).await?,
+                };
+                let event = Event::default()
+                    .event(job.status.clone())
+                    .json_data(job.view())
+                    .unwrap();
+                Some((Ok::<_, Infallible>(event), (None, updates, job.finished())))
+            }
+        },
+    );
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+// the next change to job id. fallen behind, whatever the store has now, so
+// the stream doesn't miss it finishing
+async fn next_update(
+    store: &Store,
+    updates: &mut broadcast::Receiver<Job>,
+    id: i64,
+) -> Option<Job> {
+    loop {
+        match updates.recv().await {
+            Ok(job) if job.id == id => return Some(job),
+            Ok(_) => continue,
+            Err(broadcast::error::RecvError::Lagged(_)) => return store.job(id).await,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Extension;
+use serde::Deserialize;
+
+use crate::cache::SolutionCache;
+use crate::picture::Pictures;
+use crate::session::Session;
+use crate::store::Store;
+use crate::{playing, slide_to};
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+// what an arrow key pushes, players are split on this so it's a setting
+#[derive(Deserialize, Clone, Copy, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Convention {
+    // up slides the tile under the blank up into it
+    #[default]
+    Tile,
+    // up swaps the blank with the tile above it
+    Blank,
+}
+
+// /slide/key?dir=up&convention=blank, arrows and wasd in the browser
+#[derive(Deserialize, Debug)]
+pub struct Key {
+    dir: Direction,
+    #[serde(default)]
+    convention: Convention,
+}
+
+// turns the key into a tile index and slides it like a click would, so it gets
+// the same checks. a key with nothing to slide that way is a rejected move
+pub async fn slide(
+    State(store): State<Store>,
+    State(pictures): State<Pictures>,
+    State(solutions): State<SolutionCache>,
+    Extension(session): Extension<Session>,
+    Query(key): Query<Key>,
+) -> Response {
+    let Some((board, _)) = playing(&store, &session).await else {
+        return Redirect::to("/first_iteration").into_response();
+    };
+    let (rows, cols) = match key.dir {
+        Direction::Up => (1, 0),
+        Direction::Down => (-1, 0),
+        Direction::Left => (0, 1),
+        Direction::Right => (0, -1),
+    };
+    let idx = match key.convention {
+        Convention::Tile => board.next_to_blank(rows, cols),
+        Convention::Blank => board.next_to_blank(-rows, -cols),
+    };
+
+    slide_to(&pictures, &store, &solutions, &session, idx)
+        .await
+        .into_response()
+}
@@ -0,0 +1,306 @@
+use askama::Template;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::{Extension, Form, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::session::{Account, Session};
+use crate::slayin::MoveMetric;
+use crate::store::Store;
+use crate::{bests, find_game, parse_board};
+
+const SHOWN: usize = 20;
+const MAX_NAME: usize = 20;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    Daily,
+    Weekly,
+    AllTime,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Time,
+    Moves,
+    Efficiency,
+}
+
+// ?rows=3&cols=3&difficulty=medium&period=weekly&metric=moves, anything left out
+// falls back to the all-time fastest 3x3 medium
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(default)]
+pub struct Filter {
+    pub(crate) rows: i32,
+    pub(crate) cols: i32,
+    pub(crate) difficulty: String,
+    pub(crate) period: Period,
+    pub(crate) metric: Metric,
+    // slayin::MoveMetric, scores only line up against ones counted the same way
+    pub(crate) move_metric: String,
+    // the day's board instead of every board of the size and difficulty
+    pub(crate) daily: Option<String>,
+}
+
+impl Default for Filter {
+    fn default() -> Filter {
+        Filter {
+            rows: 3,
+            cols: 3,
+            difficulty: String::from("medium"),
+            period: Period::AllTime,
+            metric: Metric::Time,
+            move_metric: String::from(MoveMetric::SingleTile.as_str()),
+            daily: None,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Serialize, Debug)]
+pub struct Entry {
+    pub name: String,
+    pub moves: i32,
+    pub optimal: i32,
+    pub elapsed_ms: i64,
+    pub efficiency: i32,
+    pub created_at: String,
+}
+
+// a solved game going onto the leaderboard
+#[derive(Clone, Debug)]
+pub(crate) struct Score {
+    pub(crate) session: String,
+    pub(crate) game_id: i64,
+    pub(crate) name: String,
+    pub(crate) rows: i32,
+    pub(crate) cols: i32,
+    pub(crate) difficulty: String,
+    pub(crate) optimal: i32,
+    pub(crate) moves: i32,
+    pub(crate) elapsed_ms: i64,
+    pub(crate) efficiency: i32,
+    pub(crate) start_board: String,
+    // tile indexes slid, space separated like Game::history
+    pub(crate) history: String,
+    pub(crate) daily: Option<String>,
+    pub(crate) metric: String,
+}
+
+impl Entry {
+    pub fn time(&self) -> String {
+        bests::clock(self.elapsed_ms)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "leaderboard.html")]
+struct LeaderboardTemplate {
+    title: String,
+    filter: Filter,
+    sides: Vec<usize>,
+    entries: Vec<Entry>,
+    error: Option<String>,
+}
+
+impl LeaderboardTemplate {
+    fn is_side(&self, side: &usize, of: &i32) -> bool {
+        *side as i32 == *of
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Submission {
+    name: String,
+    history: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JsonSubmission {
+    name: String,
+    history: Vec<usize>,
+}
+
+#[derive(Serialize, Debug)]
+struct Rejected {
+    error: String,
+}
+
+pub async fn page(State(store): State<Store>, Query(filter): Query<Filter>) -> Response {
+    render(&store, filter, None).await
+}
+
+pub async fn api(State(store): State<Store>, Query(filter): Query<Filter>) -> impl IntoResponse {
+    Json(entries(&store, &filter).await)
+}
+
+// the form on a solved board, straight to the leaderboard it made it onto
+pub async fn submit_form(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    account: Option<Extension<Account>>,
+    Form(submission): Form<Submission>,
+) -> Response {
+    let history: Result<Vec<usize>, _> = submission
+        .history
+        .split_whitespace()
+        .map(|idx| idx.parse())
+        .collect();
+    let Ok(history) = history else {
+        let error = String::from("the move history is garbled");
+        return render(&store, Filter::default(), Some(error)).await;
+    };
+
+    let name = signed(account, submission.name);
+    match submit(&store, &session, &name, &history).await {
+        Ok(Filter {
+            daily: Some(date), ..
+        }) => Redirect::to(&format!("/daily/leaderboard?date={}", date)).into_response(),
+        Ok(filter) => Redirect::to(&format!(
+            "/leaderboard?rows={}&cols={}&difficulty={}&move_metric={}",
+            filter.rows, filter.cols, filter.difficulty, filter.move_metric
+        ))
+        .into_response(),
+        Err(error) => render(&store, Filter::default(), Some(error)).await,
+    }
+}
+
+pub async fn submit_json(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    account: Option<Extension<Account>>,
+    Json(submission): Json<JsonSubmission>,
+) -> Response {
+    let name = signed(account, submission.name);
+    match submit(&store, &session, &name, &submission.history).await {
+        Ok(filter) => (StatusCode::CREATED, Json(filter)).into_response(),
+        Err(error) => (StatusCode::UNPROCESSABLE_ENTITY, Json(Rejected { error })).into_response(),
+    }
+}
+
+// someone logged in goes on the board as their account, whatever name they typed
+fn signed(account: Option<Extension<Account>>, name: String) -> String {
+    match account {
+        Some(Extension(account)) => account.name,
+        None => name,
+    }
+}
+
+async fn render(store: &Store, filter: Filter, error: Option<String>) -> Response {
+    let status = match error {
+        Some(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        None => StatusCode::OK,
+    };
+    let template = LeaderboardTemplate {
+        title: String::from("leaderboard"),
+        entries: entries(store, &filter).await,
+        filter,
+        sides: (crate::slayin::MIN_SIDE..=crate::slayin::MAX_SIDE).collect(),
+        error,
+    };
+    (status, template).into_response()
+}
+
+async fn entries(store: &Store, filter: &Filter) -> Vec<Entry> {
+    if let Some(date) = &filter.daily {
+        return daily(store, date).await;
+    }
+    store.scores(filter, SHOWN).await
+}
+
+// everyone's go at the day's puzzle, fewest moves first
+pub async fn daily(store: &Store, date: &str) -> Vec<Entry> {
+    store.daily_scores(date, SHOWN).await
+}
+
+// the session's solved game goes on the board, but only if the history sent in is
+// the one the server saw and replaying it from the start board really solves it
+async fn submit(
+    store: &Store,
+    session: &Session,
+    name: &str,
+    history: &[usize],
+) -> Result<Filter, String> {
+    let name: String = name.trim().chars().take(MAX_NAME).collect();
+    if name.is_empty() {
+        return Err(String::from("put a name on it"));
+    }
+
+    let game = find_game(store, session)
+        .await
+        .ok_or_else(|| String::from("there's no game to submit"))?;
+    if game.start_board.is_empty() {
+        return Err(String::from("this game started before scores were kept"));
+    }
+    if game.solved_moves.is_none() {
+        return Err(String::from("solve it first"));
+    }
+
+    // slides made after solving it don't count. a slide can be a push of several
+    // tiles, so where it got solved is found by playing the game back, once,
+    // up to the first slide that solves it
+    let start = parse_board(&game.start_board);
+    let played = game.slides();
+    let mut board = start.clone();
+    let mut solved_in = played.len();
+    for (no, &idx) in played.iter().enumerate() {
+        if board.slide_line(idx).is_none() {
+            break;
+        }
+        if board.is_solved() {
+            solved_in = no + 1;
+            break;
+        }
+    }
+    if history.len() < solved_in || history[..solved_in] != played[..solved_in] {
+        return Err(String::from("that's not how the game went"));
+    }
+    let history = &history[..solved_in];
+    let moves = match start.replay(history, game.move_metric()) {
+        Ok((board, moves)) if board.is_solved() => moves as i32,
+        Ok(_) => return Err(String::from("those moves don't solve the board")),
+        Err(no) => return Err(format!("move {} isn't in line with the blank", no + 1)),
+    };
+
+    let efficiency = match moves {
+        0 => 100,
+        moves => game.start_distance * 100 / moves,
+    };
+    let score = Score {
+        session: session.0.clone(),
+        game_id: game.id,
+        name,
+        rows: game.rows,
+        cols: game.cols,
+        difficulty: game.difficulty().to_owned(),
+        optimal: game.start_distance,
+        moves,
+        elapsed_ms: game.elapsed_ms(),
+        efficiency,
+        start_board: game.start_board.clone(),
+        history: history
+            .iter()
+            .map(|idx| idx.to_string())
+            .collect::<Vec<String>>()
+            .join(" "),
+        daily: game.daily.clone(),
+        metric: game.metric.clone(),
+    };
+    if !store.add_score(&score).await {
+        return Err(match game.daily {
+            Some(_) => String::from("you're already on today's leaderboard"),
+            None => String::from("this game is already on the leaderboard"),
+        });
+    }
+
+    Ok(Filter {
+        rows: game.rows,
+        cols: game.cols,
+        difficulty: game.difficulty().to_owned(),
+        move_metric: game.metric,
+        daily: game.daily,
+        ..Filter::default()
+    })
+}
@@ -0,0 +1,1369 @@
+use askama::Template;
+use axum::extract::{FromRef, Query};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{Redirect, Response};
+use axum::routing::{get, post};
+use axum::{extract::State, response::IntoResponse};
+use axum::{Extension, Router};
+use axum_extra::extract::Form;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
+use tower_http::LatencyUnit;
+use tracing::Level;
+
+// add tailwindcss
+// playable board
+
+mod account;
+mod bests;
+mod cache;
+pub mod config;
+mod daily;
+mod history;
+mod jobs;
+mod keys;
+mod leaderboard;
+mod limit;
+pub mod logging;
+mod metrics;
+mod picture;
+mod race;
+mod session;
+pub mod slayin;
+mod solution;
+mod store;
+mod ws;
+
+// handlers that only need storage keep taking State<Store>
+#[derive(Clone)]
+struct AppState {
+    store: Store,
+    rooms: race::Rooms,
+    solutions: SolutionCache,
+    jobs: jobs::Jobs,
+    limits: limit::Limits,
+    sessions: session::Sessions,
+    pictures: picture::Pictures,
+}
+
+impl FromRef<AppState> for Store {
+    fn from_ref(state: &AppState) -> Store {
+        state.store.clone()
+    }
+}
+
+impl FromRef<AppState> for race::Rooms {
+    fn from_ref(state: &AppState) -> race::Rooms {
+        state.rooms.clone()
+    }
+}
+
+impl FromRef<AppState> for SolutionCache {
+    fn from_ref(state: &AppState) -> SolutionCache {
+        state.solutions.clone()
+    }
+}
+
+impl FromRef<AppState> for limit::Limits {
+    fn from_ref(state: &AppState) -> limit::Limits {
+        state.limits.clone()
+    }
+}
+
+impl FromRef<AppState> for session::Sessions {
+    fn from_ref(state: &AppState) -> session::Sessions {
+        state.sessions.clone()
+    }
+}
+
+impl FromRef<AppState> for jobs::Jobs {
+    fn from_ref(state: &AppState) -> jobs::Jobs {
+        state.jobs.clone()
+    }
+}
+
+impl FromRef<AppState> for picture::Pictures {
+    fn from_ref(state: &AppState) -> picture::Pictures {
+        state.pictures.clone()
+    }
+}
+
+// the router with the default config, what the tests run against
+pub fn app(pool: SqlitePool) -> Router {
+    router(Arc::new(SqliteStore::new(pool)), &Config::default())
+}
+
+// the router on whichever store the config asks for, the database only gets
+// opened and migrated for sqlite
+pub async fn build(config: &Config) -> Router {
+    router(open_store(config).await, config)
+}
+
+// serves on listener until shutdown resolves. then it stops taking connections,
+// stops the solves still running so the requests waiting on them can answer,
+// waits for everything in flight and closes the store
+pub async fn serve(
+    listener: TcpListener,
+    config: &Config,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
+    let store = open_store(config).await;
+    let app = router(store.clone(), config);
+
+    // the peer's address, for limiting by ip
+    let app = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown.await;
+            tracing::info!("shutting down, draining requests");
+            slayin::stop_solving();
+        })
+        .await
+        .unwrap();
+    store.close().await;
+    tracing::info!("stopped");
+}
+
+async fn open_store(config: &Config) -> Store {
+    match config.store {
+        Backend::Sqlite => Arc::new(SqliteStore::new(
+            initialize_database(&config.database_url).await,
+        )),
+        Backend::Memory => Arc::new(MemoryStore::new()),
+    }
+}
+
+fn router(store: Store, config: &Config) -> Router {
+    let size = NonZeroUsize::new(config.cache_size).unwrap();
+    let solutions = SolutionCache::new(store.clone(), size, config.cache_keep);
+    let state = AppState {
+        jobs: jobs::Jobs::start(
+            store.clone(),
+            solutions.clone(),
+            config.workers,
+            config.job_budget,
+        ),
+        solutions,
+        sessions: session::Sessions {
+            store: store.clone(),
+            secure: config.secure_cookies,
+        },
+        store,
+        rooms: race::Rooms::default(),
+        limits: limit::Limits::new(config),
+        pictures: picture::Pictures(Arc::new(config.pictures.clone())),
+    };
+
+    // everything that runs the solver, rate limited and sharing its slots.
+    // connecting a websocket counts as a request, its messages get counted in
+    // ws and race
+    let solving = Router::new()
+        .route(
+            "/first_iteration",
+            get(path_index_whole_board), /* .post(slide) */
+        )
+        .route("/rest_iterations", get(path_index_iterations))
+        .route("/slide", get(slide))
+        .route("/slide/key", get(keys::slide))
+        .route("/hint", get(hint))
+        .route("/metric", post(set_metric))
+        .route("/solve", get(solve))
+        .route("/solve/stream", get(solve_stream))
+        .route("/edit", get(edit).put(check_edit))
+        .route("/edit/play", post(play_edit))
+        .route("/edit/solve", post(solve_edit))
+        .route("/p/:code", get(permalink))
+        .route("/api/jobs", post(jobs::submit))
+        .route("/history/:id", get(history::replay))
+        .route("/u/:name/history/:id", get(history::user_replay))
+        .route("/daily", get(daily::page))
+        .route("/race", post(race::create))
+        .route("/ws", get(ws::handler))
+        .route("/race/:code/ws", get(race::handler))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.limits.clone(),
+            limit::requests,
+        ));
+
+    // rate limited the same but without a solver slot. the password hashing
+    // behind logging in is slow on purpose, and a stream is held open for as
+    // long as it plays back
+    let counted = Router::new()
+        .route("/history/:id/stream", get(history::stream))
+        .route("/u/:name/history/:id/stream", get(history::user_stream))
+        .route("/register", post(account::register))
+        .route("/login", post(account::login))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.limits.clone(),
+            limit::counted,
+        ));
+
+    Router::new()
+        .route("/", get(handler))
+        .route("/loading", get(loading))
+        .route("/playback", get(playback))
+        .route("/api/solver/cache", get(cache::stats))
+        .route("/api/jobs/:id", get(jobs::status))
+        .route("/api/jobs/:id/events", get(jobs::events))
+        .route("/loadz", get(loadz))
+        .route("/metrics", get(metrics::page))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route(
+            "/picture",
+            get(picture::page)
+                .post(picture::upload)
+                .layer(axum::extract::DefaultBodyLimit::max(picture::MAX_UPLOAD)),
+        )
+        .route("/picture/clear", post(picture::clear))
+        .route("/bests", get(bests::page))
+        .route("/history", get(history::page))
+        .route("/u/:name/history", get(history::user_page))
+        .route("/register", get(account::register_page))
+        .route("/login", get(account::login_page))
+        .route("/logout", post(account::logout))
+        .route("/profile", get(account::own))
+        .route("/u/:name", get(account::profile))
+        .route("/daily/leaderboard", get(daily::leaderboard))
+        .route(
+            "/leaderboard",
+            get(leaderboard::page).post(leaderboard::submit_form),
+        )
+        .route(
+            "/api/leaderboard",
+            get(leaderboard::api).post(leaderboard::submit_json),
+        )
+        .route("/play", get(ws::play))
+        .route("/race", get(race::lobby))
+        .route("/race/join", get(race::join))
+        .route("/race/:code", get(race::room))
+        .merge(solving)
+        .merge(counted)
+        .nest_service(
+            "/templates",
+            tower_http::services::ServeFile::new(config.assets.join("output.css")),
+        )
+        .nest_service(
+            "/pictures",
+            tower_http::services::ServeDir::new(&config.pictures),
+        )
+        .layer(axum::extract::DefaultBodyLimit::max(config.max_body))
+        .layer(axum::middleware::from_fn(limit::explain))
+        .layer(axum::middleware::from_fn(metrics::track))
+        .layer(axum::middleware::from_fn_with_state(
+            state.sessions.clone(),
+            session::ensure_session,
+        ))
+        // a span per request, its method and path on every line logged under it
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+                .on_response(
+                    DefaultOnResponse::new()
+                        .level(Level::INFO)
+                        .latency_unit(LatencyUnit::Millis),
+                ),
+        )
+        .with_state(state)
+}
+
+#[derive(Template)]
+#[template(path = "loadz.html")]
+struct LoadzTemplate {
+    title: String,
+}
+
+// up at all, for restarting a server that's stuck
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+// whether to send it traffic, not while it's shutting down or can't reach its
+// database
+async fn readyz(State(store): State<Store>) -> (StatusCode, &'static str) {
+    if slayin::stopping() {
+        return (StatusCode::SERVICE_UNAVAILABLE, "shutting down");
+    }
+    if !store.ping().await {
+        return (StatusCode::SERVICE_UNAVAILABLE, "store unavailable");
+    }
+    (StatusCode::OK, "ready")
+}
+
+async fn loadz() -> impl IntoResponse {
+    LoadzTemplate {
+        title: String::from("loadzzzing..."),
+    }
+}
+
+#[derive(Template)]
+#[template(path = "edit.html")]
+struct Edit {
+    title: String,
+    rows: usize,
+    cols: usize,
+    sides: Vec<usize>,
+    cells: Vec<Vec<(usize, String)>>,
+    errors: Vec<String>,
+    verdict: Option<String>,
+    playable: bool,
+    // the board's /p/ link once it's playable
+    code: Option<String>,
+}
+
+impl Edit {
+    // the blank shows up as an empty square
+    fn new(rows: usize, cols: usize, cells: &[String]) -> Edit {
+        Edit {
+            title: String::from("editing"),
+            rows,
+            cols,
+            sides: (slayin::MIN_SIDE..=slayin::MAX_SIDE).collect(),
+            cells: cells
+                .iter()
+                .map(|cell| match cell.trim() {
+                    "0" => String::new(),
+                    cell => cell.to_owned(),
+                })
+                .enumerate()
+                .collect::<Vec<_>>()
+                .chunks(cols)
+                .map(|chunk| chunk.to_vec())
+                .collect(),
+            errors: Vec::new(),
+            verdict: None,
+            playable: false,
+            code: None,
+        }
+    }
+
+    fn is_size(&self, side: &usize, of: &usize) -> bool {
+        side == of
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct EditSize {
+    rows: Option<usize>,
+    cols: Option<usize>,
+}
+
+// repeated cell=.. fields, so this goes through axum_extra's Form
+#[derive(Deserialize, Debug)]
+struct EditForm {
+    rows: usize,
+    cols: usize,
+    #[serde(default)]
+    cell: Vec<String>,
+}
+
+// the session's current board to start from, or a solved one of the asked for size
+async fn edit(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    Query(size): Query<EditSize>,
+) -> impl IntoResponse {
+    let board = match (size.rows, size.cols) {
+        (None, None) if find_game(&store, &session).await.is_some() => {
+            current_board(&store, &session).await
+        }
+        (rows, cols) => {
+            let rows = rows.unwrap_or(3).clamp(slayin::MIN_SIDE, slayin::MAX_SIDE);
+            let cols = cols.unwrap_or(3).clamp(slayin::MIN_SIDE, slayin::MAX_SIDE);
+            Board::with_size(Board::goal(rows, cols), rows, cols)
+        }
+    };
+
+    let cells: Vec<String> = board.cells.iter().map(|cell| cell.to_string()).collect();
+    Edit::new(board.rows, board.cols, &cells)
+}
+
+async fn check_edit(
+    State(limits): State<limit::Limits>,
+    Form(form): Form<EditForm>,
+) -> Result<Edit, limit::Limited> {
+    Ok(check_board(&form, &limits, MoveMetric::default()).await?.1)
+}
+
+async fn play_edit(
+    State(store): State<Store>,
+    State(pictures): State<picture::Pictures>,
+    State(limits): State<limit::Limits>,
+    Extension(session): Extension<Session>,
+    Form(form): Form<EditForm>,
+) -> Response {
+    let metric = session_metric(&store, &session).await;
+    let (board, page) = match check_board(&form, &limits, metric).await {
+        Ok(checked) => checked,
+        Err(limited) => return limited.into_response(),
+    };
+    let Some((board, distance)) = board else {
+        return page.into_response();
+    };
+    let game = begin_game(&store, &session, &board, distance, metric).await;
+
+    SlideTemplate {
+        title: String::from("your board"),
+        path: board_rows(&board),
+        hint: None,
+        feedback: None,
+        best: store.best_before(&session, &game).await,
+        share: daily::share(&game),
+        game,
+        picture: picture::tiles(&pictures, &store, &session, &board).await,
+    }
+    .into_response()
+}
+
+async fn solve_edit(
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    State(limits): State<limit::Limits>,
+    Extension(session): Extension<Session>,
+    Form(form): Form<EditForm>,
+) -> Response {
+    let metric = session_metric(&store, &session).await;
+    let (board, page) = match check_board(&form, &limits, metric).await {
+        Ok(checked) => checked,
+        Err(limited) => return limited.into_response(),
+    };
+    let Some((board, distance)) = board else {
+        return page.into_response();
+    };
+    begin_game(&store, &session, &board, distance, metric).await;
+
+    path_index_iterations(State(store), State(solutions), Extension(session))
+        .await
+        .into_response()
+}
+
+// a board someone shared, straight into the slide view. codes that don't decode
+// or boards that can't be played end up in the editor saying why
+async fn permalink(
+    State(store): State<Store>,
+    State(pictures): State<picture::Pictures>,
+    State(limits): State<limit::Limits>,
+    Extension(session): Extension<Session>,
+    axum::extract::Path(code): axum::extract::Path<String>,
+) -> Response {
+    let board = match Board::from_code(&code) {
+        Ok(board) => board,
+        Err(error) => {
+            let goal = Board::goal(3, 3);
+            let cells: Vec<String> = goal.iter().map(|cell| cell.to_string()).collect();
+            let mut page = Edit::new(3, 3, &cells);
+            page.errors
+                .push(format!("{} isn't a board code: {}", code, error));
+            return (StatusCode::BAD_REQUEST, page).into_response();
+        }
+    };
+
+    let form = EditForm {
+        rows: board.rows,
+        cols: board.cols,
+        cell: board.cells.iter().map(|cell| cell.to_string()).collect(),
+    };
+    let metric = session_metric(&store, &session).await;
+    let (board, page) = match check_board(&form, &limits, metric).await {
+        Ok(checked) => checked,
+        Err(limited) => return limited.into_response(),
+    };
+    let Some((board, distance)) = board else {
+        return (StatusCode::BAD_REQUEST, page).into_response();
+    };
+    let game = begin_game(&store, &session, &board, distance, metric).await;
+
+    SlideTemplate {
+        title: String::from("a shared board"),
+        path: board_rows(&board),
+        hint: None,
+        feedback: None,
+        best: store.best_before(&session, &game).await,
+        share: daily::share(&game),
+        game,
+        picture: picture::tiles(&pictures, &store, &session, &board).await,
+    }
+    .into_response()
+}
+
+// validates what was typed in and explains whether it can be solved, the board
+// only comes back when it's solvable and the solver can find its way home from it,
+// with how many moves that takes counted by metric. boards bigger than the server
+// takes don't get that far
+async fn check_board(
+    form: &EditForm,
+    limits: &limit::Limits,
+    metric: MoveMetric,
+) -> Result<(Option<(Board, usize)>, Edit), limit::Limited> {
+    let rows = form.rows.clamp(slayin::MIN_SIDE, slayin::MAX_SIDE);
+    let cols = form.cols.clamp(slayin::MIN_SIDE, slayin::MAX_SIDE);
+    limits.check_size(rows, cols)?;
+    let mut page = Edit::new(rows, cols, &form.cell);
+
+    let mut cells = Vec::new();
+    for cell in &form.cell {
+        match cell.trim() {
+            "" => cells.push(0),
+            cell => match cell.parse::<i32>() {
+                Ok(cell) => cells.push(cell),
+                Err(_) => page.errors.push(format!("{:?} isn't a number", cell)),
+            },
+        }
+    }
+    if !page.errors.is_empty() {
+        return Ok((None, page));
+    }
+
+    let board = match Board::validate(cells, rows, cols) {
+        Ok(board) => board,
+        Err(errors) => {
+            page.errors = errors;
+            return Ok((None, page));
+        }
+    };
+
+    let (solvable, explanation) = board.solvability();
+    if !solvable {
+        page.verdict = Some(explanation);
+        return Ok((None, page));
+    }
+
+    let searched = board.clone();
+    let distance = solver_task(move || searched.optimal_distance_with(metric)).await;
+    match distance {
+        Some(distance) => {
+            page.verdict = Some(format!("{}, in {} moves at best", explanation, distance));
+            page.playable = true;
+            page.code = Some(board.code());
+            Ok((Some((board, distance)), page))
+        }
+        None => {
+            page.verdict = Some(format!(
+                "{}, but it's too far from solved for the solver to work out, try fewer tiles out of place",
+                explanation
+            ));
+            Ok((None, page))
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "loading.html")]
+struct LoadingTemplate {
+    title: String,
+}
+
+async fn loading() -> impl IntoResponse {
+    LoadingTemplate {
+        title: String::from("loading..."),
+    }
+}
+
+#[derive(Template)]
+#[template(path = "index.html")]
+struct HelloTemplate {
+    title: String,
+}
+
+async fn handler() -> impl IntoResponse {
+    HelloTemplate {
+        title: String::from("index page"),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Input {
+    index: i32,
+}
+
+// add query for the index
+
+// START, solves from wherever the player has got to rather than the scramble.
+// the steps go into solutions, the game and its board don't change
+async fn path_index_iterations(
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    Extension(session): Extension<Session>,
+) -> impl IntoResponse {
+    if find_game(&store, &session).await.is_none() {
+        return Redirect::to("/first_iteration").into_response();
+    }
+    let Some(steps) = solution::from_here(&store, &solutions, &session).await else {
+        return too_hard().into_response();
+    };
+
+    let path: Vec<Vec<i32>> = steps[0]
+        .cells
+        .chunks(steps[0].cols)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+
+    let template = PathTany {
+        title: String::from("an 7elw w anta 7elw"),
+        path,
+        step: 1,
+        steps: steps.len(),
+        is_last_step: false,
+        interval: DEFAULT_INTERVAL_MS,
+        playing: true,
+        base: SOLUTION_PLAYBACK,
+        stream: SOLUTION_STREAM,
+    };
+
+    template.render().unwrap().into_response()
+}
+
+// start db
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{migrate::MigrateDatabase, ConnectOptions, Sqlite, SqlitePool};
+
+use crate::cache::SolutionCache;
+use crate::config::{Backend, Config};
+use crate::metrics::METRICS;
+use crate::session::Session;
+use crate::slayin::{Board, MoveMetric};
+use crate::store::{MemoryStore, SqliteStore, Store};
+
+pub const DB_URL: &str = "sqlite://sqlite.db";
+const SLOW_QUERY: Duration = Duration::from_millis(100);
+
+pub async fn initialize_database(db_url: &str) -> SqlitePool {
+    // db start
+    if !Sqlite::database_exists(db_url).await.unwrap_or(false) {
+        match Sqlite::create_database(db_url).await {
+            Ok(_) => tracing::info!(db_url, "created the database"),
+            Err(error) => panic!("error: {}", error),
+        }
+    } else {
+        tracing::info!(db_url, "database already exists");
+    }
+
+    // every statement gets logged with how long it took, at debug so it's only
+    // there when asked for. slow ones show up as warnings either way
+    let options = SqliteConnectOptions::from_str(db_url)
+        .unwrap()
+        .log_statements(log::LevelFilter::Debug)
+        .log_slow_statements(log::LevelFilter::Warn, SLOW_QUERY);
+    let pool = SqlitePool::connect_with(options).await.unwrap();
+
+    //migration script, built into the binary so it runs from anywhere
+    let migration_results = sqlx::migrate!("./migrations").run(&pool).await;
+
+    match migration_results {
+        Ok(_) => tracing::info!("migrated"),
+        Err(error) => {
+            panic!("error: {}", error);
+        }
+    }
+    pool
+}
+
+// end db
+
+#[derive(Template, Debug)]
+#[template(path = "iteration.html")]
+struct Path {
+    title: String,
+    path: Vec<Vec<(usize, i32)>>,
+    picture: Option<String>,
+    game: Game,
+}
+
+impl Path {
+    fn is_blank(&self, cell: &i32) -> bool {
+        *cell == 0
+    }
+
+    fn label(&self, idx: &usize, cell: &i32) -> String {
+        tile_label(*idx, *cell, self.game.cols as usize)
+    }
+
+    fn blank(&self) -> usize {
+        self.path
+            .concat()
+            .iter()
+            .find(|(_, cell)| *cell == 0)
+            .unwrap()
+            .0
+    }
+}
+
+// what a screen reader says for a slide button, "tile 5, row 2 column 1"
+fn tile_label(idx: usize, cell: i32, cols: usize) -> String {
+    let place = format!("row {} column {}", idx / cols + 1, idx % cols + 1);
+    match cell {
+        0 => format!("blank, {}", place),
+        cell => format!("tile {}, {}", cell, place),
+    }
+}
+
+#[derive(Template, Debug)]
+#[template(path = "iteration2.html")]
+struct PathTany {
+    title: String,
+    path: Vec<Vec<i32>>,
+    step: i32,
+    // boards in the whole solution, the start included
+    steps: usize,
+    is_last_step: bool,
+    interval: u64,
+    playing: bool,
+    // where the controls and the stream go, see SOLUTION_PLAYBACK
+    base: &'static str,
+    stream: &'static str,
+}
+
+impl PathTany {
+    fn is_blank(&self, cell: &i32) -> bool {
+        *cell == 0
+    }
+}
+
+// one frame of the solution playback, sent over /solve/stream
+#[derive(Template, Debug)]
+#[template(path = "playback_frame.html")]
+struct PlaybackFrame {
+    path: Vec<Vec<i32>>,
+    step: i32,
+    steps: usize,
+    is_last_step: bool,
+    interval: u64,
+    playing: bool,
+    base: &'static str,
+}
+
+impl PlaybackFrame {
+    fn is_blank(&self, cell: &i32) -> bool {
+        *cell == 0
+    }
+}
+
+#[derive(Template)]
+#[template(path = "slide.html")]
+struct SlideTemplate {
+    title: String,
+    path: Vec<Vec<(usize, i32)>>,
+    hint: Option<usize>,
+    feedback: Option<MoveFeedback>,
+    game: Game,
+    // tile images instead of numbers, see picture::tiles
+    picture: Option<String>,
+    // the personal best this game is up against
+    best: Option<bests::Best>,
+    // something to paste to friends once the puzzle of the day is solved
+    share: Option<String>,
+}
+
+impl SlideTemplate {
+    fn is_blank(&self, cell: &i32) -> bool {
+        *cell == 0
+    }
+
+    fn is_hint(&self, idx: &usize) -> bool {
+        self.hint == Some(*idx)
+    }
+
+    fn pushes_count_once(&self) -> bool {
+        self.game.move_metric() == MoveMetric::MultiTile
+    }
+
+    fn label(&self, idx: &usize, cell: &i32) -> String {
+        tile_label(*idx, *cell, self.game.cols as usize)
+    }
+
+    fn blank(&self) -> usize {
+        self.path
+            .concat()
+            .iter()
+            .find(|(_, cell)| *cell == 0)
+            .unwrap()
+            .0
+    }
+
+    // the solve against the best before it, moves made after solving don't count
+    fn beats(&self, best: &bests::Best) -> bool {
+        let moves = self.game.solved_moves.unwrap_or(self.game.moves);
+        moves < best.moves || self.game.elapsed_ms() < best.elapsed_ms
+    }
+
+    // optimal moves worth of progress per move made, 100 is playing perfectly
+    fn efficiency(&self) -> i32 {
+        if self.game.moves == 0 {
+            return 100;
+        }
+        ((self.game.start_distance - self.game.distance) * 100 / self.game.moves).max(0)
+    }
+}
+
+// how the last slide changed the optimal distance to the goal
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+enum MoveFeedback {
+    Closer,
+    Same,
+    Away,
+    // the tile wasn't in line with the blank, nothing moved
+    Blocked,
+    // the move count can't change halfway through a game
+    Started,
+}
+
+#[derive(sqlx::FromRow, Clone, Debug)]
+struct Game {
+    id: i64,
+    start_distance: i32,
+    distance: i32,
+    moves: i32,
+    rows: i32,
+    cols: i32,
+    started_ms: i64,
+    // set by the move that solves it, the clock stops there
+    solved_ms: Option<i64>,
+    start_board: String,
+    // tile indexes slid, space separated
+    history: String,
+    // the date when it's the puzzle of the day
+    daily: Option<String>,
+    solved_moves: Option<i32>,
+    // slayin::MoveMetric, how pushes of several tiles get counted
+    metric: String,
+}
+
+impl Game {
+    fn elapsed_ms(&self) -> i64 {
+        self.solved_ms.unwrap_or_else(now_ms) - self.started_ms
+    }
+
+    fn clock(&self) -> String {
+        bests::clock(self.elapsed_ms())
+    }
+
+    fn difficulty(&self) -> &'static str {
+        bests::difficulty(self.start_distance)
+    }
+
+    fn move_metric(&self) -> MoveMetric {
+        MoveMetric::parse(&self.metric).unwrap_or_default()
+    }
+
+    // where the game started, as a /p/ code
+    fn code(&self) -> Option<String> {
+        match self.start_board.is_empty() {
+            true => None,
+            false => Some(parse_board(&self.start_board).code()),
+        }
+    }
+
+    fn slides(&self) -> Vec<usize> {
+        self.history
+            .split_whitespace()
+            .map(|idx| idx.parse().unwrap())
+            .collect()
+    }
+}
+
+// runs the solver off the async threads, its span stays under the request's
+async fn solver_task<T: Send + 'static>(solve: impl FnOnce() -> T + Send + 'static) -> T {
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || span.in_scope(solve))
+        .await
+        .unwrap()
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+#[derive(Deserialize, Debug)]
+struct Edxd {
+    idx: i32,
+}
+
+// hx-get="/solve?index={{step}}
+async fn solve(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    Query(query): Query<Input>,
+) -> impl IntoResponse {
+    let steps = solution::latest(&store, &session).await;
+    if steps.is_empty() {
+        return Redirect::to("/first_iteration").into_response();
+    }
+    let index = (query.index.max(0) as usize).min(steps.len() - 1);
+    let board = &steps[index];
+
+    let new: Vec<Vec<i32>> = board
+        .cells
+        .chunks(board.cols)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+    if index == steps.len() - 1 {
+        return PathTany {
+            title: String::from("الف مبرو9و9و9وك"),
+            path: new,
+            step: index as i32,
+            steps: steps.len(),
+            is_last_step: true,
+            interval: DEFAULT_INTERVAL_MS,
+            playing: false,
+            base: SOLUTION_PLAYBACK,
+            stream: SOLUTION_STREAM,
+        }
+        .render()
+        .unwrap()
+        .into_response();
+    }
+    let template = PathTany {
+        title: String::from("ya 7lal ya 3leem ya rzak ya kareem"),
+        path: new,
+        step: index as i32 + 1,
+        steps: steps.len(),
+        is_last_step: false,
+        interval: DEFAULT_INTERVAL_MS,
+        playing: false,
+        base: SOLUTION_PLAYBACK,
+        stream: SOLUTION_STREAM,
+    };
+
+    template.render().unwrap().into_response()
+}
+
+const DEFAULT_INTERVAL_MS: u64 = 100;
+// the solution viewer's controls step through /playback, history::replay has
+// its own
+const SOLUTION_PLAYBACK: &str = "/playback";
+const SOLUTION_STREAM: &str = "/solve/stream";
+
+fn default_interval() -> u64 {
+    DEFAULT_INTERVAL_MS
+}
+
+#[derive(Deserialize, Debug)]
+struct Playback {
+    // index of the next board to show, the one on screen is step - 1. the
+    // first when it's left out
+    #[serde(default)]
+    step: usize,
+    #[serde(default = "default_interval")]
+    interval: u64,
+    #[serde(default)]
+    playing: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct Stream {
+    from: usize,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn playback_frame(steps: &[Board], step: usize, interval: u64, playing: bool) -> PlaybackFrame {
+    let shown = step.clamp(1, steps.len()) - 1;
+    let board = &steps[shown];
+    PlaybackFrame {
+        path: board
+            .cells
+            .chunks(board.cols)
+            .map(|chunk| chunk.to_vec())
+            .collect(),
+        step: shown as i32 + 1,
+        steps: steps.len(),
+        is_last_step: shown == steps.len() - 1,
+        interval: interval.clamp(10, 5000),
+        playing,
+        base: SOLUTION_PLAYBACK,
+    }
+}
+
+// pause, resume, stepping, jumping and speed changes, renders the player at a
+// step and (re)connects to the stream from there when playing. a page of its
+// own too, /playback?step=5 links straight to the 5th board
+async fn playback(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    Query(query): Query<Playback>,
+) -> Response {
+    let steps = solution::latest(&store, &session).await;
+    // a link from a session that hasn't solved anything yet
+    if steps.is_empty() {
+        return Redirect::to("/first_iteration").into_response();
+    }
+    let frame = playback_frame(&steps, query.step, query.interval, query.playing);
+
+    PathTany {
+        title: String::from("an 7elw w anta 7elw"),
+        path: frame.path,
+        step: frame.step,
+        steps: frame.steps,
+        is_last_step: frame.is_last_step,
+        interval: frame.interval,
+        playing: frame.playing,
+        base: SOLUTION_PLAYBACK,
+        stream: SOLUTION_STREAM,
+    }
+    .into_response()
+}
+
+// reads the solution once and pushes a frame every interval ms, the last
+// frame swaps the player out of band which closes the event source
+async fn solve_stream(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+    Query(query): Query<Stream>,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let steps = solution::latest(&store, &session).await;
+    let interval = query.interval.clamp(10, 5000);
+
+    let frames = (query.from + 1..=steps.len())
+        .map(|step| {
+            playback_frame(&steps, step, interval, true)
+                .render()
+                .unwrap()
+        })
+        .collect::<Vec<String>>();
+
+    let stream = futures::stream::iter(frames).then(move |frame| async move {
+        tokio::time::sleep(Duration::from_millis(interval)).await;
+        Ok(Event::default().event("step").data(frame))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn slide(
+    State(store): State<Store>,
+    State(pictures): State<picture::Pictures>,
+    State(solutions): State<SolutionCache>,
+    Extension(session): Extension<Session>,
+    Query(edxd): Query<Edxd>,
+) -> Response {
+    // a link from a session that hasn't started a game yet
+    if find_game(&store, &session).await.is_none() {
+        return Redirect::to("/first_iteration").into_response();
+    }
+    slide_to(
+        &pictures,
+        &store,
+        &solutions,
+        &session,
+        usize::try_from(edxd.idx).ok(),
+    )
+    .await
+    .into_response()
+}
+
+// the board after sliding the tile at idx, clicked or picked by keys::slide
+async fn slide_to(
+    pictures: &picture::Pictures,
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+    idx: Option<usize>,
+) -> SlideTemplate {
+    let moved = match idx {
+        Some(idx) => apply_move(store, solutions, session, idx).await,
+        None => None,
+    };
+    let (board, game, feedback) = match moved {
+        Some(moved) => moved,
+        // not next to the blank, the board stays as it is
+        None => (
+            current_board(store, session).await,
+            current_game(store, session).await,
+            Some(MoveFeedback::Blocked),
+        ),
+    };
+
+    SlideTemplate {
+        title: String::from("I like to move it move it"),
+        path: board_rows(&board),
+        hint: None,
+        feedback,
+        best: store.best_before(session, &game).await,
+        share: daily::share(&game),
+        game,
+        picture: picture::tiles(pictures, store, session, &board).await,
+    }
+}
+
+// slides the tile at idx and everything between it and the blank, and scores
+// the move against the optimal distance. None if the tile isn't in the blank's
+// row or column. the feedback is None when the board got too far from solved
+// for the solver to tell. the path it finds is cached, so the hint that usually
+// comes next doesn't search again
+async fn apply_move(
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+    idx: usize,
+) -> Option<(Board, Game, Option<MoveFeedback>)> {
+    let mut board = current_board(store, session).await;
+    let tiles = board.slide_line(idx)?;
+    store.set_board(session, &board).await;
+
+    let game = current_game(store, session).await;
+    let metric = game.move_metric();
+    let distance = solutions
+        .solve(&board, metric)
+        .await
+        .map(|steps| steps.len() as i32 - 1);
+    let feedback = distance.map(|distance| match distance.cmp(&game.distance) {
+        std::cmp::Ordering::Less => MoveFeedback::Closer,
+        std::cmp::Ordering::Equal => MoveFeedback::Same,
+        std::cmp::Ordering::Greater => MoveFeedback::Away,
+    });
+    // the first time it's solved stops the clock, later moves keep counting but
+    // don't make it into the record again
+    let solves = board.is_solved() && game.solved_ms.is_none();
+    store
+        .record_move(
+            game.id,
+            idx,
+            metric.count(tiles) as i32,
+            distance.unwrap_or(game.distance),
+            solves.then(now_ms),
+        )
+        .await;
+    let game = current_game(store, session).await;
+    METRICS.moved("solo");
+    if solves {
+        store.add_result(session, &game).await;
+        METRICS.game_finished();
+    }
+
+    Some((board, game, feedback))
+}
+
+// wipes the session's old game and makes board the one row 0 points at, moves
+// get counted the way the old game counted them. None if the solver can't work
+// out how far it is from solved
+async fn start_game(store: &Store, session: &Session, board: &Board) -> Option<Game> {
+    let metric = session_metric(store, session).await;
+    start_game_with(store, session, board, metric).await
+}
+
+async fn start_game_with(
+    store: &Store,
+    session: &Session,
+    board: &Board,
+    metric: MoveMetric,
+) -> Option<Game> {
+    let searched = board.clone();
+    let distance = solver_task(move || searched.optimal_distance_with(metric)).await?;
+    Some(begin_game(store, session, board, distance, metric).await)
+}
+
+// start_game for a board whose distance is already worked out, check_board's
+// or the day's
+async fn begin_game(
+    store: &Store,
+    session: &Session,
+    board: &Board,
+    distance: usize,
+    metric: MoveMetric,
+) -> Game {
+    METRICS.game_started();
+    store
+        .start_game(session, board, distance as i32, metric)
+        .await
+}
+
+// how the session's moves get counted, the next game keeps counting them that way
+async fn session_metric(store: &Store, session: &Session) -> MoveMetric {
+    match find_game(store, session).await {
+        Some(game) => game.move_metric(),
+        None => MoveMetric::default(),
+    }
+}
+
+// the board the player is currently on, row 0 gets updated by every slide
+async fn current_board(store: &Store, session: &Session) -> Board {
+    store.board(session).await.unwrap()
+}
+
+// "1 2 3, 4 5 6, 7 8 0" with an optional ",atoobees compelete" on the last
+// step, a row per comma so the shape comes along with the cells
+fn parse_board(path_json: &str) -> Board {
+    let rows: Vec<Vec<i32>> = path_json
+        .split(',')
+        .take_while(|row| !row.trim().starts_with("atoobees"))
+        .map(|row| {
+            row.split_whitespace()
+                .map(|s| s.parse::<i32>().unwrap())
+                .collect()
+        })
+        .collect();
+
+    Board::with_size(rows.concat(), rows.len(), rows[0].len())
+}
+
+// inverse of parse_board
+fn format_board(board: &Board) -> String {
+    board
+        .cells
+        .chunks(board.cols)
+        .map(|row| {
+            row.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// board in the (idx, cell) rows the slide buttons are rendered from
+fn board_rows(board: &Board) -> Vec<Vec<(usize, i32)>> {
+    board
+        .cells
+        .iter()
+        .copied()
+        .enumerate()
+        .collect::<Vec<_>>()
+        .chunks(board.cols)
+        .map(|chunk| chunk.to_vec())
+        .collect()
+}
+
+// the blank's next spot on the optimal path is the tile to slide, like
+// Board::hint_with but the path comes out of the cache
+async fn hint(
+    State(store): State<Store>,
+    State(pictures): State<picture::Pictures>,
+    State(solutions): State<SolutionCache>,
+    Extension(session): Extension<Session>,
+) -> Response {
+    let Some((board, game)) = playing(&store, &session).await else {
+        return Redirect::to("/first_iteration").into_response();
+    };
+    let steps = solutions.solve(&board, game.move_metric()).await;
+    let hint = steps.and_then(|steps| Some(Board::find_zero(&steps.get(1)?.cells)));
+
+    SlideTemplate {
+        title: String::from("psst.."),
+        path: board_rows(&board),
+        hint,
+        feedback: None,
+        best: store.best_before(&session, &game).await,
+        share: daily::share(&game),
+        game,
+        picture: picture::tiles(&pictures, &store, &session, &board).await,
+    }
+    .into_response()
+}
+
+#[derive(Deserialize, Debug)]
+struct MetricForm {
+    metric: String,
+}
+
+// switches how pushes get counted, only before the first move so the moves
+// made so far all got counted the same way. the distances are worked out again
+// under the new count
+async fn set_metric(
+    State(store): State<Store>,
+    State(pictures): State<picture::Pictures>,
+    Extension(session): Extension<Session>,
+    Form(form): Form<MetricForm>,
+) -> Response {
+    let Some(metric) = MoveMetric::parse(&form.metric) else {
+        return (StatusCode::BAD_REQUEST, "no such move metric").into_response();
+    };
+    let Some((board, game)) = playing(&store, &session).await else {
+        return Redirect::to("/first_iteration").into_response();
+    };
+    let feedback = match game.moves {
+        0 => {
+            let searched = board.clone();
+            let distance = solver_task(move || searched.optimal_distance_with(metric)).await;
+            let Some(distance) = distance else {
+                return too_hard().into_response();
+            };
+            store.set_metric(game.id, metric, distance as i32).await;
+            None
+        }
+        _ => Some(MoveFeedback::Started),
+    };
+    let game = current_game(&store, &session).await;
+
+    SlideTemplate {
+        title: String::from("I like to move it move it"),
+        path: board_rows(&board),
+        hint: None,
+        feedback,
+        best: store.best_before(&session, &game).await,
+        share: daily::share(&game),
+        game,
+        picture: picture::tiles(&pictures, &store, &session, &board).await,
+    }
+    .into_response()
+}
+
+async fn current_game(store: &Store, session: &Session) -> Game {
+    find_game(store, session).await.unwrap()
+}
+
+async fn find_game(store: &Store, session: &Session) -> Option<Game> {
+    store.game(session).await
+}
+
+// the session's board and game, None before it's started one
+async fn playing(store: &Store, session: &Session) -> Option<(Board, Game)> {
+    let game = find_game(store, session).await?;
+    Some((store.board(session).await?, game))
+}
+
+#[derive(Template)]
+#[template(path = "too_hard.html")]
+struct TooHard {
+    title: String,
+}
+
+// the solver ran out of budget, usually a big board that's far from solved
+fn too_hard() -> TooHard {
+    TooHard {
+        title: String::from("too hard"),
+    }
+}
+
+// make it take just the first vec and adjust the html of it too
+async fn path_index_whole_board(
+    State(store): State<Store>,
+    State(pictures): State<picture::Pictures>,
+    Extension(session): Extension<Session>,
+) -> impl IntoResponse {
+    let board = slayin::Board::random_board();
+    let mut new = Vec::new();
+    let mut ok = Vec::new();
+    for (i, &e) in board.cells.iter().enumerate() {
+        ok.push((i, e));
+        if i == 2 || i == 5 || i == 8 {
+            new.push(ok.clone());
+            ok.clear()
+        }
+    }
+    // a random 3x3 is always within the solver's budget
+    let game = start_game(&store, &session, &board).await.unwrap();
+
+    let template = Path {
+        title: String::from("path page"),
+        path: new.clone(),
+        picture: picture::tiles(&pictures, &store, &session, &board).await,
+        game,
+    };
+
+    template
+}
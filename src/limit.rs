@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use askama::Template;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Config;
+use crate::session::Session;
+
+// buckets kept before the full ones get thrown out, a full bucket is the same
+// as no bucket
+const BUCKETS: usize = 10_000;
+
+// how much the expensive routes get used, see requests. the solver's the
+// expensive part, most of them run it on every call
+#[derive(Clone)]
+pub(crate) struct Limits(Arc<Inner>);
+
+struct Inner {
+    per_ip: f64,
+    per_session: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    solves: Arc<Semaphore>,
+    max_tiles: usize,
+}
+
+// a minute's worth of requests that refills a bit at a time, so a burst is fine
+// but keeping it up isn't
+struct Bucket {
+    tokens: f64,
+    per_minute: f64,
+    filled: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let minutes = now.duration_since(self.filled).as_secs_f64() / 60.0;
+        self.tokens = (self.tokens + minutes * self.per_minute).min(self.per_minute);
+        self.filled = now;
+    }
+}
+
+impl Limits {
+    pub(crate) fn new(config: &Config) -> Limits {
+        Limits(Arc::new(Inner {
+            per_ip: config.per_ip as f64,
+            per_session: config.per_session as f64,
+            buckets: Mutex::new(HashMap::new()),
+            solves: Arc::new(Semaphore::new(config.max_solves)),
+            max_tiles: config.max_tiles,
+        }))
+    }
+
+    // one out of key's bucket, or the seconds until there is one
+    fn take(&self, key: String, per_minute: f64) -> Result<(), u64> {
+        let mut buckets = self.0.buckets.lock().unwrap();
+        if buckets.len() >= BUCKETS {
+            buckets.retain(|_, bucket| {
+                bucket.refill();
+                bucket.tokens < bucket.per_minute
+            });
+        }
+
+        let bucket = buckets.entry(key).or_insert_with(|| Bucket {
+            tokens: per_minute,
+            per_minute,
+            filled: Instant::now(),
+        });
+        bucket.refill();
+        if bucket.tokens < 1.0 {
+            return Err(((1.0 - bucket.tokens) * 60.0 / per_minute).ceil() as u64);
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    // a token out of the ip's bucket and the session's, a 429 when either's run out
+    pub(crate) fn count(&self, ip: Option<IpAddr>, session: Option<&str>) -> Result<(), Limited> {
+        let slow_down = "slow down a little, that's a lot of boards in a minute";
+        if let Some(ip) = ip {
+            let key = format!("ip {}", ip);
+            if let Err(seconds) = self.take(key, self.0.per_ip) {
+                return Err(Limited::slow_down(slow_down, seconds));
+            }
+        }
+        if let Some(session) = session {
+            let key = format!("session {}", session);
+            if let Err(seconds) = self.take(key, self.0.per_session) {
+                return Err(Limited::slow_down(slow_down, seconds));
+            }
+        }
+        Ok(())
+    }
+
+    // count, then one of the solver's slots, given back when the permit's
+    // dropped. what every request to the solving routes and every websocket
+    // message that solves goes through
+    pub(crate) fn check(
+        &self,
+        ip: Option<IpAddr>,
+        session: Option<&str>,
+    ) -> Result<OwnedSemaphorePermit, Limited> {
+        self.count(ip, session)?;
+        self.0.solves.clone().try_acquire_owned().map_err(|_| {
+            let busy = "the solver's busy with other boards, try again in a moment";
+            Limited::slow_down(busy, 1)
+        })
+    }
+
+    // 413 for boards with more tiles than the server's willing to solve
+    pub(crate) fn check_size(&self, rows: usize, cols: usize) -> Result<(), Limited> {
+        match rows * cols > self.0.max_tiles {
+            true => Err(Limited::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "a {}x{} board is more than this server solves, {} tiles at most",
+                    rows, cols, self.0.max_tiles
+                ),
+            )),
+            false => Ok(()),
+        }
+    }
+}
+
+// a request turned away, explain renders it for whoever asked
+#[derive(Debug)]
+pub(crate) struct Limited {
+    status: StatusCode,
+    // the websockets send just this
+    pub(crate) message: String,
+    retry_after: Option<u64>,
+}
+
+#[derive(Clone)]
+struct Message(String);
+
+impl Limited {
+    pub(crate) fn new(status: StatusCode, message: String) -> Limited {
+        Limited {
+            status,
+            message,
+            retry_after: None,
+        }
+    }
+
+    fn slow_down(message: &str, seconds: u64) -> Limited {
+        Limited {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            message: message.to_owned(),
+            retry_after: Some(seconds.max(1)),
+        }
+    }
+}
+
+impl IntoResponse for Limited {
+    fn into_response(self) -> Response {
+        let mut response = self.status.into_response();
+        if let Some(seconds) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response.extensions_mut().insert(Message(self.message));
+        response
+    }
+}
+
+// counted against the ip and the session both, then one of the solver's slots
+// held for as long as the handler runs. out of either is a 429
+pub async fn requests(
+    State(limits): State<Limits>,
+    connected: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = connected.map(|ConnectInfo(addr)| addr.ip());
+    let session = request.extensions().get::<Session>();
+    let session = session.map(|Session(session)| session.as_str());
+    let _solving = match limits.check(ip, session) {
+        Ok(solving) => solving,
+        Err(limited) => return limited.into_response(),
+    };
+    next.run(request).await
+}
+
+// just the counting, for routes that are slow or held open but don't solve, so
+// they never take one of the solver's slots
+pub async fn counted(
+    State(limits): State<Limits>,
+    connected: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = connected.map(|ConnectInfo(addr)| addr.ip());
+    let session = request.extensions().get::<Session>();
+    let session = session.map(|Session(session)| session.as_str());
+    if let Err(limited) = limits.count(ip, session) {
+        return limited.into_response();
+    }
+    next.run(request).await
+}
+
+#[derive(Template)]
+#[template(path = "limited.html")]
+struct LimitedPage {
+    title: String,
+    message: String,
+}
+
+#[derive(Template)]
+#[template(path = "limited_notice.html")]
+struct LimitedNotice {
+    message: String,
+}
+
+#[derive(Serialize)]
+struct Rejected {
+    error: String,
+}
+
+// every 413 and 429 on the way out, as json for the api, a notice htmx puts
+// above the page for its requests (the board it was going to swap stays put)
+// and a page of its own otherwise. bodies over the limit come back from axum
+// as plain text, they get the same
+pub async fn explain(request: Request, next: Next) -> Response {
+    let htmx = request.headers().contains_key("hx-request");
+    let api = request.uri().path().starts_with("/api/");
+
+    let response = next.run(request).await;
+    let status = response.status();
+    let message = match response.extensions().get::<Message>() {
+        Some(Message(message)) => message.clone(),
+        None if status == StatusCode::PAYLOAD_TOO_LARGE => {
+            String::from("that's more than the server takes in one request")
+        }
+        None if status == StatusCode::TOO_MANY_REQUESTS => {
+            String::from("slow down a little and try again")
+        }
+        None => return response,
+    };
+
+    let mut explained = if api {
+        (status, Json(Rejected { error: message })).into_response()
+    } else if htmx {
+        let headers = [("HX-Retarget", "#notice"), ("HX-Reswap", "innerHTML")];
+        (status, headers, LimitedNotice { message }).into_response()
+    } else {
+        let title = match status {
+            StatusCode::TOO_MANY_REQUESTS => String::from("slow down"),
+            _ => String::from("too big"),
+        };
+        (status, LimitedPage { title, message }).into_response()
+    };
+    if let Some(retry_after) = response.headers().get(header::RETRY_AFTER) {
+        explained
+            .headers_mut()
+            .insert(header::RETRY_AFTER, retry_after.clone());
+    }
+    explained
+}
@@ -0,0 +1,17 @@
+use tracing_subscriber::EnvFilter;
+
+use crate::config::{Config, LogFormat};
+
+// sets up the process's logs. RUST_LOG, when it's set, says what gets through
+// instead of the log level, e.g. RUST_LOG=info,slidin_puzzle::slayin=trace.
+// sqlx's query log lines come through here too, see initialize_database
+pub fn init(config: &Config) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.log_level));
+    let logs = tracing_subscriber::fmt().with_env_filter(filter);
+
+    match config.log_format {
+        LogFormat::Pretty => logs.pretty().init(),
+        LogFormat::Json => logs.json().flatten_event(true).init(),
+    }
+}
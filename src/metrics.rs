@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::cache::SolutionCache;
+
+// seconds, for requests and solves alike
+const SECONDS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+// boards a search closed, the budget tops out around the last one
+const NODES: &[f64] = &[10.0, 100.0, 1_000.0, 10_000.0, 100_000.0, 1_000_000.0];
+
+// counted since the server started and served as /metrics. a static rather than
+// app state since the solver counts from down in slayin, which has no state
+pub(crate) static METRICS: Metrics = Metrics::new();
+
+pub(crate) struct Metrics {
+    // (method, route, status)
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    request_seconds: Mutex<BTreeMap<String, Histogram>>,
+    games_started: AtomicU64,
+    games_finished: AtomicU64,
+    // "solo" or "race"
+    moves: Mutex<BTreeMap<&'static str, u64>>,
+    // (algorithm, move metric, "solved" or "gave_up")
+    solver_runs: Mutex<BTreeMap<(&'static str, &'static str, &'static str), u64>>,
+    solver_seconds: Mutex<BTreeMap<&'static str, Histogram>>,
+    solver_nodes: Mutex<BTreeMap<&'static str, Histogram>>,
+}
+
+// cumulative like prometheus wants them, a value lands in every bucket it fits
+struct Histogram {
+    bounds: &'static [f64],
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram {
+            bounds,
+            buckets: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, bucket) in self.bounds.iter().zip(&mut self.buckets) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}le=\"{}\"}} {}",
+                name, labels, bound, bucket
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}le=\"+Inf\"}} {}",
+            name, labels, self.count
+        );
+        let labels = labels.trim_end_matches(',');
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, self.count);
+    }
+}
+
+impl Metrics {
+    const fn new() -> Metrics {
+        Metrics {
+            requests: Mutex::new(BTreeMap::new()),
+            request_seconds: Mutex::new(BTreeMap::new()),
+            games_started: AtomicU64::new(0),
+            games_finished: AtomicU64::new(0),
+            moves: Mutex::new(BTreeMap::new()),
+            solver_runs: Mutex::new(BTreeMap::new()),
+            solver_seconds: Mutex::new(BTreeMap::new()),
+            solver_nodes: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub(crate) fn game_started(&self) {
+        self.games_started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn game_finished(&self) {
+        self.games_finished.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn moved(&self, mode: &'static str) {
+        *self.moves.lock().unwrap().entry(mode).or_default() += 1;
+    }
+
+    pub(crate) fn solver_ran(
+        &self,
+        algorithm: &'static str,
+        metric: &'static str,
+        solved: bool,
+        nodes: usize,
+        seconds: f64,
+    ) {
+        let outcome = if solved { "solved" } else { "gave_up" };
+        *self
+            .solver_runs
+            .lock()
+            .unwrap()
+            .entry((algorithm, metric, outcome))
+            .or_default() += 1;
+        self.solver_seconds
+            .lock()
+            .unwrap()
+            .entry(algorithm)
+            .or_insert_with(|| Histogram::new(SECONDS))
+            .observe(seconds);
+        self.solver_nodes
+            .lock()
+            .unwrap()
+            .entry(algorithm)
+            .or_insert_with(|| Histogram::new(NODES))
+            .observe(nodes as f64);
+    }
+
+    fn request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry((method.to_owned(), route.to_owned(), status))
+            .or_default() += 1;
+        self.request_seconds
+            .lock()
+            .unwrap()
+            .entry(route.to_owned())
+            .or_insert_with(|| Histogram::new(SECONDS))
+            .observe(seconds);
+    }
+
+    fn render(&self, cache: &SolutionCache) -> String {
+        let mut out = String::new();
+
+        help(
+            &mut out,
+            "slidin_http_requests_total",
+            "counter",
+            "Requests answered.",
+        );
+        for ((method, route, status), count) in self.requests.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "slidin_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                method,
+                label(route),
+                status,
+                count
+            );
+        }
+        help(
+            &mut out,
+            "slidin_http_request_duration_seconds",
+            "histogram",
+            "How long requests took to answer.",
+        );
+        for (route, histogram) in self.request_seconds.lock().unwrap().iter() {
+            let labels = format!("route=\"{}\",", label(route));
+            histogram.render(&mut out, "slidin_http_request_duration_seconds", &labels);
+        }
+
+        help(
+            &mut out,
+            "slidin_games_started_total",
+            "counter",
+            "Games started.",
+        );
+        let started = self.games_started.load(Ordering::Relaxed);
+        let _ = writeln!(out, "slidin_games_started_total {}", started);
+        help(
+            &mut out,
+            "slidin_games_finished_total",
+            "counter",
+            "Games solved.",
+        );
+        let finished = self.games_finished.load(Ordering::Relaxed);
+        let _ = writeln!(out, "slidin_games_finished_total {}", finished);
+        help(
+            &mut out,
+            "slidin_moves_total",
+            "counter",
+            "Slides that moved a tile.",
+        );
+        for (mode, count) in self.moves.lock().unwrap().iter() {
+            let _ = writeln!(out, "slidin_moves_total{{mode=\"{}\"}} {}", mode, count);
+        }
+
+        help(
+            &mut out,
+            "slidin_solver_runs_total",
+            "counter",
+            "Searches the solver ran.",
+        );
+        for ((algorithm, metric, outcome), count) in self.solver_runs.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "slidin_solver_runs_total{{algorithm=\"{}\",metric=\"{}\",outcome=\"{}\"}} {}",
+                algorithm, metric, outcome, count
+            );
+        }
+        help(
+            &mut out,
+            "slidin_solver_duration_seconds",
+            "histogram",
+            "How long searches took.",
+        );
+        for (algorithm, histogram) in self.solver_seconds.lock().unwrap().iter() {
+            let labels = format!("algorithm=\"{}\",", algorithm);
+            histogram.render(&mut out, "slidin_solver_duration_seconds", &labels);
+        }
+        help(
+            &mut out,
+            "slidin_solver_nodes_expanded",
+            "histogram",
+            "Boards a search closed.",
+        );
+        for (algorithm, histogram) in self.solver_nodes.lock().unwrap().iter() {
+            let labels = format!("algorithm=\"{}\",", algorithm);
+            histogram.render(&mut out, "slidin_solver_nodes_expanded", &labels);
+        }
+
+        let stats = cache.stats();
+        help(
+            &mut out,
+            "slidin_solution_cache_lookups_total",
+            "counter",
+            "Solves asked of the solution cache.",
+        );
+        for (result, count) in [
+            ("memory_hit", stats.memory_hits),
+            ("store_hit", stats.store_hits),
+            ("miss", stats.misses),
+        ] {
+            let _ = writeln!(
+                out,
+                "slidin_solution_cache_lookups_total{{result=\"{}\"}} {}",
+                result, count
+            );
+        }
+        help(
+            &mut out,
+            "slidin_solution_cache_hit_ratio",
+            "gauge",
+            "Share of lookups answered without solving.",
+        );
+        let lookups = stats.memory_hits + stats.store_hits + stats.misses;
+        let ratio = match lookups {
+            0 => 0.0,
+            lookups => (stats.memory_hits + stats.store_hits) as f64 / lookups as f64,
+        };
+        let _ = writeln!(out, "slidin_solution_cache_hit_ratio {}", ratio);
+        help(
+            &mut out,
+            "slidin_solution_cache_evictions_total",
+            "counter",
+            "Solutions pushed out to make room.",
+        );
+        for (layer, count) in [
+            ("memory", stats.memory_evictions),
+            ("store", stats.store_evictions),
+        ] {
+            let _ = writeln!(
+                out,
+                "slidin_solution_cache_evictions_total{{layer=\"{}\"}} {}",
+                layer, count
+            );
+        }
+        help(
+            &mut out,
+            "slidin_solution_cache_entries",
+            "gauge",
+            "Solutions held in memory.",
+        );
+        let _ = writeln!(out, "slidin_solution_cache_entries {}", stats.entries);
+
+        out
+    }
+}
+
+fn help(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// counts every request under the route it matched, not the path, so /p/:code
+// is one line however many boards get shared
+pub async fn track(matched: Option<MatchedPath>, request: Request, next: Next) -> Response {
+    let route = matched.map_or_else(
+        || String::from("unmatched"),
+        |path| path.as_str().to_owned(),
+    );
+    let method = request.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+    let seconds = started.elapsed().as_secs_f64();
+    METRICS.request(&method, &route, response.status().as_u16(), seconds);
+    response
+}
+
+// prometheus' text exposition format
+pub async fn page(State(cache): State<SolutionCache>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        METRICS.render(&cache),
+    )
+}
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use askama::Template;
+use axum::extract::{Multipart, State};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Extension;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use rand::distributions::{Alphanumeric, DistString};
+
+use crate::session::Session;
+use crate::slayin::Board;
+use crate::store::Store;
+
+// where the uploads are kept, Config::pictures. served as /pictures
+#[derive(Clone)]
+pub(crate) struct Pictures(pub(crate) Arc<PathBuf>);
+
+// uploads get shrunk to this before they're kept, tiles are cut out of that copy
+const MAX_PX: u32 = 1024;
+const TILE_PX: u32 = 96;
+
+pub const MAX_UPLOAD: usize = 10 * 1024 * 1024;
+
+#[derive(Template)]
+#[template(path = "picture.html")]
+struct PictureTemplate {
+    title: String,
+    picture: Option<String>,
+    error: Option<String>,
+}
+
+pub async fn page(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+) -> impl IntoResponse {
+    PictureTemplate {
+        title: String::from("picture"),
+        picture: store.active_picture(&session).await,
+        error: None,
+    }
+}
+
+// keeps the image for the session and starts a game to play it on. it's kept
+// under a random token rather than its id, so the picture's only found by
+// whoever was shown it
+pub async fn upload(
+    State(store): State<Store>,
+    State(pictures): State<Pictures>,
+    Extension(session): Extension<Session>,
+    mut multipart: Multipart,
+) -> Response {
+    let mut bytes = None;
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some("picture") {
+            bytes = field.bytes().await.ok();
+        }
+    }
+    let Some(bytes) = bytes else {
+        return failed(&store, &session, String::from("pick a picture first")).await;
+    };
+
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let dir = pictures.0.join(&token);
+    // decoding and shrinking a big picture takes a while, off the async threads
+    let kept = tokio::task::spawn_blocking(move || keep(&bytes, &dir))
+        .await
+        .unwrap();
+    if let Err(error) = kept {
+        return failed(&store, &session, error).await;
+    }
+    store.add_picture(&session, &token).await;
+
+    Redirect::to("/first_iteration").into_response()
+}
+
+// saves the upload as dir/original.png, shrunk if it's big
+fn keep(bytes: &[u8], dir: &Path) -> Result<(), String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|error| format!("that's not a picture: {}", error))?;
+    if image.width() < TILE_PX || image.height() < TILE_PX {
+        return Err(format!("pictures need to be at least {0}x{0}", TILE_PX));
+    }
+
+    std::fs::create_dir_all(dir).unwrap();
+    let image = match image.width().max(image.height()) > MAX_PX {
+        true => image.resize(MAX_PX, MAX_PX, FilterType::Triangle),
+        false => image,
+    };
+    image.save(dir.join("original.png")).unwrap();
+    Ok(())
+}
+
+// back to plain numbers
+pub async fn clear(
+    State(store): State<Store>,
+    Extension(session): Extension<Session>,
+) -> impl IntoResponse {
+    store.clear_pictures(&session).await;
+
+    Redirect::to("/picture")
+}
+
+async fn failed(store: &Store, session: &Session, error: String) -> Response {
+    PictureTemplate {
+        title: String::from("picture"),
+        picture: store.active_picture(session).await,
+        error: Some(error),
+    }
+    .into_response()
+}
+
+// where the session's picture tiles for this board's shape are served from,
+// "/pictures/{token}/3x4" holds 1.png up to 11.png. None plays with numbers
+pub async fn tiles(
+    pictures: &Pictures,
+    store: &Store,
+    session: &Session,
+    board: &Board,
+) -> Option<String> {
+    let token = store.active_picture(session).await?;
+    let shape = format!("{}x{}", board.rows, board.cols);
+    let (rows, cols) = (board.rows as u32, board.cols as u32);
+
+    let dir = pictures.0.join(&token);
+    let sliced = dir.join(&shape);
+    // each shape gets cut the first time a board of it is shown
+    if !sliced.exists() {
+        tokio::task::spawn_blocking(move || slice(&dir, rows, cols))
+            .await
+            .unwrap()
+            .ok()?;
+    }
+
+    Some(format!("/pictures/{}/{}", token, shape))
+}
+
+// crops the biggest rows:cols piece out of the middle of the picture and cuts it
+// into the goal board's tiles, tile n is where n sits when the board is solved
+fn slice(dir: &Path, rows: u32, cols: u32) -> image::ImageResult<()> {
+    let picture = image::open(dir.join("original.png"))?;
+    let (width, height) = picture.dimensions();
+    let side = (width / cols).min(height / rows);
+    let cropped = picture
+        .crop_imm(
+            (width - side * cols) / 2,
+            (height - side * rows) / 2,
+            side * cols,
+            side * rows,
+        )
+        .resize_exact(TILE_PX * cols, TILE_PX * rows, FilterType::Triangle);
+
+    // sliced into a temp dir first so a half cut shape never gets served
+    let shape = format!("{}x{}", rows, cols);
+    let partial: PathBuf = dir.join(format!("{}.partial", shape));
+    std::fs::create_dir_all(&partial)?;
+    for tile in 1..rows * cols {
+        let (row, col) = ((tile - 1) / cols, (tile - 1) % cols);
+        save_tile(
+            &cropped,
+            col * TILE_PX,
+            row * TILE_PX,
+            &partial.join(format!("{}.png", tile)),
+        )?;
+    }
+    // two boards of the same shape being shown at once both cut it, either copy will do
+    let sliced = dir.join(shape);
+    if std::fs::rename(&partial, &sliced).is_err() && sliced.exists() {
+        std::fs::remove_dir_all(&partial)?;
+    }
+    Ok(())
+}
+
+fn save_tile(picture: &DynamicImage, x: u32, y: u32, path: &Path) -> image::ImageResult<()> {
+    picture.crop_imm(x, y, TILE_PX, TILE_PX).save(path)
+}
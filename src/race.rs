@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+
+use askama::Template;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Extension;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::limit::Limits;
+use crate::metrics::METRICS;
+use crate::session::Session;
+use crate::slayin::Board;
+use crate::store::Store;
+use crate::{parse_board, solver_task};
+
+const SEATS: i32 = 2;
+
+// no 0/O or 1/I so codes survive being read out loud
+const CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+// one channel per room, a message on it means "something changed, read the race again"
+#[derive(Clone, Default)]
+pub struct Rooms(Arc<Mutex<HashMap<String, broadcast::Sender<()>>>>);
+
+impl Rooms {
+    fn subscribe(&self, code: &str) -> broadcast::Receiver<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .entry(code.to_owned())
+            .or_insert_with(|| broadcast::channel(16).0)
+            .subscribe()
+    }
+
+    fn notify(&self, code: &str) {
+        if let Some(sender) = self.0.lock().unwrap().get(code) {
+            let _ = sender.send(());
+        }
+    }
+
+    // drops the room's channel once nobody is listening anymore
+    fn leave(&self, code: &str) {
+        let mut rooms = self.0.lock().unwrap();
+        if rooms
+            .get(code)
+            .is_some_and(|sender| sender.receiver_count() == 0)
+        {
+            rooms.remove(code);
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub(crate) struct Race {
+    pub(crate) id: i64,
+    pub(crate) code: String,
+    pub(crate) start_board: String,
+    pub(crate) distance: i32,
+    pub(crate) winner: Option<i32>,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+pub(crate) struct RacePlayer {
+    pub(crate) seat: i32,
+    pub(crate) board: String,
+    pub(crate) moves: i32,
+    pub(crate) finished: bool,
+}
+
+// what the browser sends over /race/:code/ws, just {"type": "move", "idx": 5}
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Move { idx: usize },
+}
+
+// the whole race goes out after every change, boards are tiny
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Race {
+        code: String,
+        you: i32,
+        distance: i32,
+        winner: Option<i32>,
+        players: Vec<PlayerState>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Serialize, Debug)]
+struct PlayerState {
+    seat: i32,
+    cells: Vec<i32>,
+    moves: i32,
+    finished: bool,
+}
+
+#[derive(Template)]
+#[template(path = "race_lobby.html")]
+struct LobbyTemplate {
+    title: String,
+    error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "race.html")]
+struct RaceTemplate {
+    title: String,
+    code: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Join {
+    code: String,
+}
+
+pub async fn lobby() -> impl IntoResponse {
+    LobbyTemplate {
+        title: String::from("race"),
+        error: None,
+    }
+}
+
+// a new room with a freshly seeded board, the creator gets sent straight in.
+// back to the lobby if the solver somehow can't work the board out
+pub async fn create(State(store): State<Store>) -> Response {
+    let seed = rand::thread_rng().gen::<i64>();
+    let board = Board::seeded_board(seed as u64);
+    let searched = board.clone();
+    let Some(distance) = solver_task(move || searched.optimal_distance()).await else {
+        let lobby = LobbyTemplate {
+            title: String::from("race"),
+            error: Some(String::from("couldn't set a board up, try again")),
+        };
+        return (StatusCode::SERVICE_UNAVAILABLE, lobby).into_response();
+    };
+    let distance = distance as i32;
+
+    let code = loop {
+        let code = new_code();
+        // code already taken, roll another one
+        if store.add_race(&code, seed, &board, distance).await {
+            break code;
+        }
+    };
+
+    Redirect::to(&format!("/race/{}", code)).into_response()
+}
+
+pub async fn join(Query(join): Query<Join>) -> impl IntoResponse {
+    Redirect::to(&format!("/race/{}", join.code.trim().to_uppercase()))
+}
+
+pub async fn room(State(store): State<Store>, Path(code): Path<String>) -> Response {
+    match store.race(&code).await {
+        Some(race) => RaceTemplate {
+            title: format!("race {}", race.code),
+            code: race.code,
+        }
+        .into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            LobbyTemplate {
+                title: String::from("race"),
+                error: Some(format!("there's no room {}", code)),
+            },
+        )
+            .into_response(),
+    }
+}
+
+pub async fn handler(
+    ws: WebSocketUpgrade,
+    State(store): State<Store>,
+    State(rooms): State<Rooms>,
+    State(limits): State<Limits>,
+    connected: Option<ConnectInfo<SocketAddr>>,
+    Extension(session): Extension<Session>,
+    Path(code): Path<String>,
+) -> impl IntoResponse {
+    let racer = Racer {
+        limits,
+        ip: connected.map(|ConnectInfo(addr)| addr.ip()),
+        session,
+    };
+    ws.on_upgrade(move |socket| race_session(socket, store, rooms, code, racer))
+}
+
+// who's on the other end of a race's socket, their moves are counted against
+// the same buckets as the solving routes
+struct Racer {
+    limits: Limits,
+    ip: Option<IpAddr>,
+    session: Session,
+}
+
+async fn race_session(
+    mut socket: WebSocket,
+    store: Store,
+    rooms: Rooms,
+    code: String,
+    racer: Racer,
+) {
+    let mut changes = rooms.subscribe(&code);
+
+    match take_seat(&store, &code, &racer.session).await {
+        Ok(seat) => {
+            rooms.notify(&code);
+            race_loop(
+                &mut socket,
+                &store,
+                &rooms,
+                &code,
+                seat,
+                &racer,
+                &mut changes,
+            )
+            .await;
+        }
+        Err(message) => {
+            let _ = send(&mut socket, &ServerMessage::Error { message }).await;
+        }
+    }
+
+    drop(changes);
+    rooms.leave(&code);
+}
+
+async fn race_loop(
+    socket: &mut WebSocket,
+    store: &Store,
+    rooms: &Rooms,
+    code: &str,
+    seat: i32,
+    racer: &Racer,
+    changes: &mut broadcast::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => continue,
+                };
+
+                let moved = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Move { idx }) => {
+                        match racer.limits.count(racer.ip, Some(&racer.session.0)) {
+                            Ok(()) => race_move(store, code, seat, idx).await,
+                            Err(limited) => Err(limited.message),
+                        }
+                    }
+                    Err(error) => Err(error.to_string()),
+                };
+                match moved {
+                    // our own state comes back through the channel with everyone else's
+                    Ok(()) => rooms.notify(code),
+                    Err(message) => {
+                        if send(socket, &ServerMessage::Error { message }).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+            change = changes.recv() => {
+                if let Err(broadcast::error::RecvError::Closed) = change {
+                    return;
+                }
+                let state = race_state(store, code, seat).await;
+                if send(socket, &state).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// the session's seat in the room, taking a free one if it doesn't have one yet
+async fn take_seat(store: &Store, code: &str, session: &Session) -> Result<i32, String> {
+    let race = store
+        .race(code)
+        .await
+        .ok_or_else(|| format!("there's no room {}", code))?;
+
+    if let Some(seat) = store.seat(race.id, session).await {
+        return Ok(seat);
+    }
+
+    let taken = store.race_players(race.id).await.len() as i32;
+    if taken >= SEATS {
+        return Err(String::from("the room is full"));
+    }
+
+    // two people grabbing the last seat at once, the store lets one through,
+    // the same session connecting twice at once gets the seat the other one took
+    let board = parse_board(&race.start_board);
+    match store.add_player(race.id, taken + 1, session, &board).await {
+        true => Ok(taken + 1),
+        false => store
+            .seat(race.id, session)
+            .await
+            .ok_or_else(|| String::from("the room is full")),
+    }
+}
+
+async fn race_move(store: &Store, code: &str, seat: i32, idx: usize) -> Result<(), String> {
+    let race = store.race(code).await.unwrap();
+    if race.winner.is_some() {
+        return Err(String::from("the race is over"));
+    }
+
+    let players = store.race_players(race.id).await;
+    if players.len() < SEATS as usize {
+        return Err(String::from("waiting for an opponent"));
+    }
+
+    let player = players.iter().find(|player| player.seat == seat).unwrap();
+    let mut board = parse_board(&player.board);
+    if !board.slide_tile(idx) {
+        return Err(format!("tile {} isn't next to the blank", idx));
+    }
+    let solved = board.is_solved();
+    METRICS.moved("race");
+
+    store.update_player(race.id, seat, &board, solved).await;
+    // first one there wins, the other player's late finish doesn't overwrite it
+    if solved {
+        store.set_winner(race.id, seat).await;
+    }
+
+    Ok(())
+}
+
+async fn race_state(store: &Store, code: &str, seat: i32) -> ServerMessage {
+    let race = store.race(code).await.unwrap();
+    let players = store
+        .race_players(race.id)
+        .await
+        .into_iter()
+        .map(|player| PlayerState {
+            seat: player.seat,
+            cells: parse_board(&player.board).cells,
+            moves: player.moves,
+            finished: player.finished,
+        })
+        .collect();
+
+    ServerMessage::Race {
+        code: race.code,
+        you: seat,
+        distance: race.distance,
+        winner: race.winner,
+        players,
+    }
+}
+
+fn new_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..6)
+        .map(|_| *CODE_CHARS.choose(&mut rng).unwrap() as char)
+        .collect()
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap();
+    socket.send(Message::Text(text)).await
+}
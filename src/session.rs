@@ -0,0 +1,98 @@
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use blake2::{Blake2s256, Digest};
+use rand::distributions::{Alphanumeric, DistString};
+
+use crate::store::Store;
+
+const COOKIE: &str = "session";
+// a login's token, see account::login
+pub(crate) const ACCOUNT_COOKIE: &str = "account";
+const USER: &str = "user-";
+// how long a login lasts before it's back to the login form
+pub(crate) const LOGIN_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+
+// who's playing, handlers pull it out with Extension<Session>
+#[derive(Clone, Debug)]
+pub struct Session(pub String);
+
+impl Session {
+    // everything an account plays is kept under this, whichever browser it's on
+    pub(crate) fn of_user(user_id: i64) -> Session {
+        Session(format!("{}{}", USER, user_id))
+    }
+}
+
+// who's logged in, there as an Extension<Account> alongside the session when
+// someone is
+#[derive(Clone, Debug)]
+pub struct Account {
+    pub(crate) id: i64,
+    pub(crate) name: String,
+}
+
+// what ensure_session needs to look logins up
+#[derive(Clone)]
+pub(crate) struct Sessions {
+    pub(crate) store: Store,
+    // only sent back over https, see Config::secure_cookies
+    pub(crate) secure: bool,
+}
+
+impl Sessions {
+    pub(crate) fn cookie(&self, name: &'static str, value: String) -> Cookie<'static> {
+        Cookie::build((name, value))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .secure(self.secure)
+            .build()
+    }
+}
+
+// what the store keeps for an account cookie's token, so the logins table on
+// its own doesn't let anyone in. the tokens are random, a fast hash will do
+pub(crate) fn token_hash(token: &str) -> String {
+    Blake2s256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// a logged in browser plays as its account, anyone else gets a session cookie
+// the first time they turn up
+pub async fn ensure_session(
+    State(sessions): State<Sessions>,
+    mut jar: CookieJar,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(token) = jar.get(ACCOUNT_COOKIE) {
+        match sessions.store.login(&token_hash(token.value())).await {
+            Some(user) => {
+                request.extensions_mut().insert(Session::of_user(user.id));
+                request.extensions_mut().insert(Account {
+                    id: user.id,
+                    name: user.name,
+                });
+                return next.run(request).await;
+            }
+            // logged out from somewhere else, or expired
+            None => jar = jar.remove(sessions.cookie(ACCOUNT_COOKIE, String::new())),
+        }
+    }
+
+    // accounts' sessions only come from a login, a made up "user-1" gets a new one
+    let (jar, id) = match jar.get(COOKIE).map(|cookie| cookie.value().to_owned()) {
+        Some(id) if !id.starts_with(USER) => (jar, id),
+        _ => {
+            let id = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+            (jar.add(sessions.cookie(COOKIE, id.clone())), id)
+        }
+    };
+
+    request.extensions_mut().insert(Session(id));
+    (jar, next.run(request).await).into_response()
+}
@@ -1,13 +1,82 @@
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::collections::{BinaryHeap, HashSet};
 use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::Instant;
 
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::cache::A_STAR;
+use crate::metrics::METRICS;
+
+// boards bigger than this take too long to play with anyway
+pub const MIN_SIDE: usize = 2;
+pub const MAX_SIDE: usize = 5;
+
+// share codes, see Board::code
+const CODE_DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+// A* gives up after popping this many boards, a hard 4x4 would eat all the memory otherwise
+pub const NODE_BUDGET: usize = 500_000;
+
+// NODE_BUDGET unless the config says otherwise, see set_node_budget
+static BUDGET: AtomicUsize = AtomicUsize::new(NODE_BUDGET);
+
+// how often a search reports how far it's got, see Board::solve_within
+pub const PROGRESS_EVERY: usize = 10_000;
+
+// set on shutdown, searches give up at their next pop so requests waiting on
+// them can answer and the server can stop
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+// how a push of several tiles along the blank's row or column is counted
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+pub enum MoveMetric {
+    // a move per tile that slid, the classic count
+    #[default]
+    SingleTile,
+    // the whole push is one move, like shoving a row on a real 15-puzzle
+    MultiTile,
+}
+
+impl MoveMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MoveMetric::SingleTile => "single_tile",
+            MoveMetric::MultiTile => "multi_tile",
+        }
+    }
+
+    pub fn parse(metric: &str) -> Option<MoveMetric> {
+        match metric {
+            "single_tile" => Some(MoveMetric::SingleTile),
+            "multi_tile" => Some(MoveMetric::MultiTile),
+            _ => None,
+        }
+    }
+
+    // moves a push of `tiles` tiles counts as
+    pub fn count(&self, tiles: usize) -> usize {
+        match self {
+            MoveMetric::SingleTile => tiles,
+            MoveMetric::MultiTile => 1,
+        }
+    }
+}
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 pub struct Board {
     pub cells: Vec<i32>,
-    parent: Option<Box<Board>>,
+    pub rows: usize,
+    pub cols: usize,
+    // Arc so a child only copies a pointer to its parent instead of the whole path
+    parent: Option<Arc<Board>>,
     zero: usize,
     g_cost: i32,
     h_cost: i32,
@@ -29,11 +98,10 @@ impl PartialOrd for Board {
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\n")?;
+        writeln!(f)?;
         for (i, cell) in self.cells.iter().enumerate() {
-            // hardcoded for 3x3 board
-            if i == 3 || i == 6 {
-                write!(f, "\n")?;
+            if i > 0 && i % self.cols == 0 {
+                writeln!(f)?;
             }
             write!(f, "{}", cell)?;
         }
@@ -41,20 +109,38 @@ impl fmt::Display for Board {
     }
 }
 
-impl Board {
-    pub fn find_zero(cells: &Vec<i32>) -> usize {
-        for i in 0..cells.len() {
-            if cells[i] == 0 {
-                return i;
-            }
-        }
+// every search from here on gives up after popping this many boards
+pub fn set_node_budget(budget: usize) {
+    BUDGET.store(budget, AtomicOrdering::Relaxed);
+}
+
+// every search running or started from here on gives up, for the server's
+// shutdown. there's no taking it back
+pub fn stop_solving() {
+    STOPPING.store(true, AtomicOrdering::Relaxed);
+}
+
+pub fn stopping() -> bool {
+    STOPPING.load(AtomicOrdering::Relaxed)
+}
 
-        unreachable!()
+impl Board {
+    pub fn find_zero(cells: &[i32]) -> usize {
+        cells.iter().position(|&x| x == 0).unwrap()
     }
+
+    // square board, 9 cells is the 3x3 it always was
     pub fn new(cells: Vec<i32>) -> Board {
+        let side = (cells.len() as f64).sqrt() as usize;
+        Self::with_size(cells, side, side)
+    }
+
+    pub fn with_size(cells: Vec<i32>, rows: usize, cols: usize) -> Board {
         let zero = Self::find_zero(&cells);
         Board {
             cells,
+            rows,
+            cols,
             parent: None,
             zero,
             g_cost: 0,
@@ -68,81 +154,285 @@ impl Board {
         let h_cost = 0;
         Board {
             cells,
-            parent: Some(Box::new(parent.clone())),
+            rows: parent.rows,
+            cols: parent.cols,
+            parent: Some(Arc::new(parent.clone())),
             zero,
             g_cost: parent.g_cost + 1,
             h_cost,
         }
     }
 
+    // 1 2 3 .. n-1 with the blank in the bottom right
+    pub fn goal(rows: usize, cols: usize) -> Vec<i32> {
+        let len = (rows * cols) as i32;
+        (1..len).chain(std::iter::once(0)).collect()
+    }
+
     // two fns to calculate_h_cost
-    fn manhatan_dist(&self, goal: &Vec<i32>) -> i32 {
+    fn manhatan_dist(&self, goal: &[i32]) -> i32 {
+        let cols = self.cols as i32;
         let mut h_cost = 0;
-        for i in 0..self.cells.len() {
-            if self.cells[i] != goal[i] && self.cells[i] != 0 {
-                let ni = i as i32 / 3;
-                let nj = i as i32 % 3;
-                let goal_i = (self.cells[i] - 1) / 3;
-                let goal_j = (self.cells[i] - 1) % 3;
+        for (i, (&cell, &goal_cell)) in self.cells.iter().zip(goal).enumerate() {
+            if cell != goal_cell && cell != 0 {
+                let ni = i as i32 / cols;
+                let nj = i as i32 % cols;
+                let goal_i = (cell - 1) / cols;
+                let goal_j = (cell - 1) % cols;
 
                 h_cost += (nj - goal_j).abs() + (ni - goal_i).abs();
             }
         }
         h_cost
     }
+
+    // a push moves up to a whole line less the blank one step each, so manhattan
+    // shrinks by at most that much per move. rounded up it's still admissible
+    // and consistent under the multi tile count
+    fn heuristic(&self, goal: &[i32], metric: MoveMetric) -> i32 {
+        let manhatan = self.manhatan_dist(goal);
+        match metric {
+            MoveMetric::SingleTile => manhatan,
+            MoveMetric::MultiTile => {
+                let longest = self.rows.max(self.cols) as i32 - 1;
+                (manhatan + longest - 1) / longest
+            }
+        }
+    }
+
     // doesn't work for now as it accumilates the h_cost
     fn _manhatan_dist_not_first_time(&self, ay_ya_eedy: usize) -> i32 {
+        let cols = self.cols as i32;
         let mut h_cost = self.parent.as_ref().unwrap().h_cost;
         // we use the i no ni in fn get_neighbors
-        let ni = ay_ya_eedy as i32 / 3;
-        let nj = ay_ya_eedy as i32 % 3;
-        let goal_i = (self.cells[ay_ya_eedy] - 1) / 3;
-        let goal_j = (self.cells[ay_ya_eedy] - 1) % 3;
+        let ni = ay_ya_eedy as i32 / cols;
+        let nj = ay_ya_eedy as i32 % cols;
+        let goal_i = (self.cells[ay_ya_eedy] - 1) / cols;
+        let goal_j = (self.cells[ay_ya_eedy] - 1) % cols;
 
         h_cost += (nj - goal_j).abs() + (ni - goal_i).abs();
         h_cost
     }
-    fn _hamming_dist(cells: &Vec<i32>, goal: &Vec<i32>) -> i32 {
-        let mut h_cost = 0;
-        for i in 0..cells.len() {
-            if cells[i] != goal[i] && cells[i] != 0 {
-                h_cost += 1;
+    fn _hamming_dist(cells: &[i32], goal: &[i32]) -> i32 {
+        cells
+            .iter()
+            .zip(goal)
+            .filter(|&(&cell, &goal_cell)| cell != goal_cell && cell != 0)
+            .count() as i32
+    }
+
+    // checks what a user typed in is a board at all: every tile from 0 to
+    // rows*cols-1 exactly once
+    pub fn validate(cells: Vec<i32>, rows: usize, cols: usize) -> Result<Board, Vec<String>> {
+        let mut errors = Vec::new();
+        if !(MIN_SIDE..=MAX_SIDE).contains(&rows) || !(MIN_SIDE..=MAX_SIDE).contains(&cols) {
+            errors.push(format!(
+                "boards go from {0}x{0} up to {1}x{1}, not {2}x{3}",
+                MIN_SIDE, MAX_SIDE, rows, cols
+            ));
+            return Err(errors);
+        }
+        if cells.len() != rows * cols {
+            errors.push(format!(
+                "a {}x{} board has {} cells, got {}",
+                rows,
+                cols,
+                rows * cols,
+                cells.len()
+            ));
+            return Err(errors);
+        }
+
+        let len = cells.len() as i32;
+        let mut seen = vec![0; cells.len()];
+        for &cell in &cells {
+            if (0..len).contains(&cell) {
+                seen[cell as usize] += 1;
+            } else {
+                errors.push(format!(
+                    "{} isn't a tile, they go from 1 to {}",
+                    cell,
+                    len - 1
+                ));
             }
         }
-        h_cost
+        for (tile, &count) in seen.iter().enumerate() {
+            let name = match tile {
+                0 => String::from("the blank"),
+                _ => format!("tile {}", tile),
+            };
+            match count {
+                0 => errors.push(format!("{} is missing", name)),
+                1 => {}
+                _ => errors.push(format!("{} is there {} times", name, count)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Board::with_size(cells, rows, cols))
+        } else {
+            Err(errors)
+        }
+    }
+
+    // short url safe name for the board: one base 62 digit for the shape, then
+    // the board's rank among all orderings of its tiles in base 62. the goal is
+    // always the same for a shape so it doesn't need to go in
+    pub fn code(&self) -> String {
+        let sides = (MAX_SIDE - MIN_SIDE + 1) as u128;
+        let shape = (self.rows - MIN_SIDE) as u128 * sides + (self.cols - MIN_SIDE) as u128;
+
+        let mut code = vec![CODE_DIGITS[shape as usize]];
+        let mut rank = Self::rank(&self.cells);
+        let mut digits = Vec::new();
+        loop {
+            digits.push(CODE_DIGITS[(rank % 62) as usize]);
+            rank /= 62;
+            if rank == 0 {
+                break;
+            }
+        }
+        code.extend(digits.iter().rev());
+        String::from_utf8(code).unwrap()
+    }
+
+    // inverse of code, only ever gives back boards whose code is exactly what came in
+    pub fn from_code(code: &str) -> Result<Board, String> {
+        let mut digits = code.bytes().map(|digit| {
+            CODE_DIGITS
+                .iter()
+                .position(|&d| d == digit)
+                .map(|d| d as u128)
+                .ok_or_else(|| format!("{:?} can't be in a board code", digit as char))
+        });
+        let sides = (MAX_SIDE - MIN_SIDE + 1) as u128;
+        let shape = digits.next().ok_or("the code is empty")??;
+        if shape >= sides * sides {
+            return Err(String::from("the code's shape isn't a board size"));
+        }
+        let rows = (shape / sides) as usize + MIN_SIDE;
+        let cols = (shape % sides) as usize + MIN_SIDE;
+
+        let digits = digits.collect::<Result<Vec<u128>, String>>()?;
+        if digits.is_empty() {
+            return Err(String::from("the code stops after the board size"));
+        }
+        if digits.len() > 1 && digits[0] == 0 {
+            return Err(String::from("the code has a leading zero"));
+        }
+        let len = rows * cols;
+        let mut rank: u128 = 0;
+        for digit in digits {
+            rank = rank
+                .checked_mul(62)
+                .and_then(|rank| rank.checked_add(digit))
+                .filter(|&rank| rank < Self::factorial(len))
+                .ok_or_else(|| format!("the code is too big for a {}x{} board", rows, cols))?;
+        }
+
+        Ok(Board::with_size(Self::unrank(rank, len), rows, cols))
+    }
+
+    // position of the ordering among all of them in lexicographic order (lehmer code)
+    fn rank(cells: &[i32]) -> u128 {
+        let len = cells.len();
+        (0..len)
+            .map(|i| {
+                let smaller_after = cells[i + 1..].iter().filter(|&&c| c < cells[i]).count();
+                smaller_after as u128 * Self::factorial(len - 1 - i)
+            })
+            .sum()
+    }
+
+    fn unrank(mut rank: u128, len: usize) -> Vec<i32> {
+        let mut left: Vec<i32> = (0..len as i32).collect();
+        let mut cells = Vec::with_capacity(len);
+        for i in (0..len).rev() {
+            let place = Self::factorial(i);
+            cells.push(left.remove((rank / place) as usize));
+            rank %= place;
+        }
+        cells
+    }
+
+    // 25! is the biggest needed and fits in a u128 with room to spare
+    fn factorial(n: usize) -> u128 {
+        (1..=n as u128).product()
+    }
+
+    pub fn is_solvable(&self) -> bool {
+        self.solvability().0
     }
 
-    // I think it works-ish, it gives you a solvable board but the inversion count is wrong
-    pub fn is_solvable(cells: &Vec<i32>) -> bool {
-        let new_vec = cells.iter().filter(|&x| *x != 0).map(|&x| x).collect();
-        // println!("new_vec={:?}", new_vec);
+    // whether the board can reach the goal and why, in words for the editor.
+    // every move keeps a parity the goal has, so a board with the other parity
+    // can never get there
+    pub fn solvability(&self) -> (bool, String) {
+        let new_vec: Vec<i32> = self.cells.iter().filter(|&x| *x != 0).copied().collect();
         let inversions_count = Self::merge_sort(&new_vec);
-        // println!("inversions_count={:?}", inversions_count);
-        if inversions_count % 2 == 0 {
-            return true;
+
+        if self.cols % 2 == 1 {
+            // sliding sideways changes nothing, sliding up or down jumps a tile over
+            // an even no. of others so the inversions keep their parity
+            let solvable = inversions_count % 2 == 0;
+            let explanation = format!(
+                "{} pairs of tiles are the wrong way round (inversions). with an odd width every move \
+                 keeps that count {}, and the goal has 0, so it {}",
+                inversions_count,
+                if solvable { "even" } else { "odd" },
+                if solvable {
+                    "can be solved"
+                } else {
+                    "can never be solved, swap any two tiles to fix it"
+                }
+            );
+            return (solvable, explanation);
         }
-        false
+
+        // with an even width sliding up or down flips the inversions' parity and
+        // moves the blank a row, so inversions + blank row keeps its parity. rows
+        // counted from 1 here, the same as the explanation shows them
+        let blank_row = Self::find_zero(&self.cells) / self.cols + 1;
+        let sum = inversions_count as usize + blank_row;
+        let solvable = sum % 2 == self.rows % 2;
+        let explanation = format!(
+            "{} inversions plus the blank on row {} makes {}. with an even width every move keeps \
+             that {}, the goal has {} (blank on the last row, no inversions), so it {}",
+            inversions_count,
+            blank_row,
+            sum,
+            if sum.is_multiple_of(2) { "even" } else { "odd" },
+            self.rows,
+            if solvable {
+                "can be solved"
+            } else {
+                "can never be solved, swap any two tiles to fix it"
+            }
+        );
+        (solvable, explanation)
     }
 
-    fn merge_sort(vec: &Vec<i32>) -> i32 {
-        let mut tmp = vec![-1; 8];
+    fn merge_sort(vec: &[i32]) -> i32 {
+        if vec.is_empty() {
+            return 0;
+        }
+        let mut tmp = vec![-1; vec.len()];
         let mut inversions_count = 0;
         Self::_merge_sort(
-            &mut vec.clone(),
+            &mut vec.to_vec(),
             0,
             tmp.len() - 1,
             &mut tmp,
             &mut inversions_count,
         );
-        // println!("tmp={:#?}", tmp);
         inversions_count
     }
 
     fn _merge_sort(
-        vec: &mut Vec<i32>,
+        vec: &mut [i32],
         st: usize,
         end: usize,
-        tmp: &mut Vec<i32>,
+        tmp: &mut [i32],
         inversions_count: &mut i32,
     ) {
         if st == end {
@@ -158,11 +448,11 @@ impl Board {
     }
 
     fn _join_sorted_arrays(
-        vec: &mut Vec<i32>,
+        vec: &mut [i32],
         st: usize,
         mid: usize,
         end: usize,
-        tmp: &mut Vec<i32>,
+        tmp: &mut [i32],
         inversions_count: &mut i32,
     ) {
         let mut k = st;
@@ -173,10 +463,7 @@ impl Board {
             if i > mid {
                 tmp[k] = vec[j];
                 j += 1;
-            } else if j > end {
-                tmp[k] = vec[i];
-                i += 1;
-            } else if vec[j] > vec[i] {
+            } else if j > end || vec[j] > vec[i] {
                 tmp[k] = vec[i];
                 i += 1;
             } else {
@@ -187,108 +474,314 @@ impl Board {
             k += 1;
         }
 
-        // println!("{:?}", &tmp[st..=end]);
         vec[st..=end].copy_from_slice(&tmp[st..=end]);
     }
 
     pub fn random_board() -> Board {
+        Self::random_board_with(&mut rand::thread_rng())
+    }
+
+    // same seed, same board, so everyone in a race gets the same scramble
+    pub fn seeded_board(seed: u64) -> Board {
+        Self::random_board_with(&mut StdRng::seed_from_u64(seed))
+    }
+
+    // same seed, same board, and one whose optimal solution is `distances` long.
+    // ChaCha rather than StdRng since StdRng is allowed to change between rand
+    // releases and this one has to stay put, it's the puzzle of the day
+    pub fn seeded_board_within(seed: u64, distances: RangeInclusive<usize>) -> Board {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        loop {
+            let board = Self::random_board_with(&mut rng);
+            if board
+                .optimal_distance()
+                .is_some_and(|distance| distances.contains(&distance))
+            {
+                return board;
+            }
+        }
+    }
+
+    fn random_board_with(rng: &mut impl Rng) -> Board {
         loop {
-            let mut rng = rand::thread_rng();
             let mut random_board = (0..9).collect::<Vec<i32>>();
-            random_board.shuffle(&mut rng);
-            // println!("random_board={:?}", random_board);
+            random_board.shuffle(rng);
 
-            if !Self::is_solvable(&random_board) {
+            let board = Board::new(random_board);
+            if !board.is_solvable() {
                 continue;
             }
-            return Board::new(random_board);
+            return board;
         }
     }
 
-    pub fn sliding_puzzle_a_star(start: Board) -> Option<Vec<Vec<i32>>> {
-        let goal = vec![1, 2, 3, 4, 5, 6, 7, 8, 0];
-        // println!("goal={:?}", goal);
+    // a span per search, with how many boards it closed and how long it took
+    fn a_star(
+        start: Board,
+        metric: MoveMetric,
+        budget: usize,
+        progress: &mut dyn FnMut(usize),
+    ) -> Option<Vec<Vec<i32>>> {
+        let span = tracing::debug_span!(
+            "solver",
+            rows = start.rows,
+            cols = start.cols,
+            metric = metric.as_str()
+        );
+        let _entered = span.enter();
+        let started = Instant::now();
+
+        let (path, nodes) = Self::search(start, metric, budget, progress);
+        progress(nodes);
+        let elapsed = started.elapsed();
+        let elapsed_ms = elapsed.as_millis() as u64;
+        METRICS.solver_ran(
+            A_STAR,
+            metric.as_str(),
+            path.is_some(),
+            nodes,
+            elapsed.as_secs_f64(),
+        );
+        match &path {
+            Some(path) => tracing::debug!(nodes, elapsed_ms, moves = path.len(), "solved"),
+            None if stopping() => tracing::info!(nodes, elapsed_ms, "stopped for shutdown"),
+            None => tracing::info!(nodes, elapsed_ms, "ran out of budget"),
+        }
+        path
+    }
+
+    // the path and the number of boards closed on the way, progress hears the
+    // count every PROGRESS_EVERY boards
+    fn search(
+        start: Board,
+        metric: MoveMetric,
+        budget: usize,
+        progress: &mut dyn FnMut(usize),
+    ) -> (Option<Vec<Vec<i32>>>, usize) {
+        let goal = Self::goal(start.rows, start.cols);
 
         if start.cells == goal {
-            return Some(Vec::new());
+            return (Some(Vec::new()), 0);
         }
 
-        // start.h_cost = start.manhatan_dist(&goal);
+        let mut start = start;
+        start.h_cost = start.heuristic(&goal, metric);
 
         let mut pq = BinaryHeap::new();
-        pq.push(start.clone());
+        pq.push(start);
 
+        // a board is only closed once it's popped, manhattan is consistent so
+        // the first pop of the goal is an optimal path
         let mut visited = HashSet::new();
-        visited.insert(start.cells);
 
-        let mut count = 0;
         while let Some(cur) = pq.pop() {
-            // println!("cur={:?}", cur);
-            // println!("pq={:?}", pq);
-            for neighbor in cur.get_neighbors(&goal) {
-                // println!("{count}");
-                // println!("neighbor={:?}", neighbor);
-                count += 1;
-                // if count >= 5 {
-                //     break;
-                // }
-                if neighbor.cells == goal {
-                    // println!("neighbor={:?}", neighbor);
-                    Self::print_path(&neighbor);
-                    // println!("g_cost == lvl? {}", neighbor.g_cost);
-                    // return Some(neighbor.g_cost);
-                    return Some(Self::return_path(&neighbor));
-                }
+            if cur.cells == goal {
+                Self::trace_path(&cur);
+                return (Some(Self::return_path(&cur)), visited.len());
+            }
+
+            if !visited.insert(cur.cells.clone()) {
+                continue;
+            }
+            if visited.len() > budget || stopping() {
+                return (None, visited.len());
+            }
+            if visited.len() % PROGRESS_EVERY == 0 {
+                progress(visited.len());
+            }
 
-                if visited.insert(neighbor.cells.clone()) {
+            for neighbor in cur.get_neighbors(&goal, metric) {
+                if !visited.contains(&neighbor.cells) {
                     pq.push(neighbor);
                 }
             }
         }
 
-        // println!("hello");
-        None
+        (None, visited.len())
     }
 
-    fn get_neighbors(&self, goal: &Vec<i32>) -> Vec<Board> {
+    fn get_neighbors(&self, goal: &[i32], metric: MoveMetric) -> Vec<Board> {
+        if metric == MoveMetric::MultiTile {
+            return self.get_pushes(goal);
+        }
+
         let og_i = self.zero;
         let mut v = Vec::new();
-        // println!("og_i={:#?}", og_i);
 
-        let i = og_i / 3;
-        // println!("i={:#?}", i);
-        let j = og_i % 3;
-        // println!("j={:#?}", j);
+        let (rows, cols) = (self.rows as i32, self.cols as i32);
+        let i = og_i as i32 / cols;
+        let j = og_i as i32 % cols;
 
         for &(di, dj) in &[(1, 0), (0, 1), (-1, 0), (0, -1)] {
-            let ni = i as i32 + di;
-            // println!("ni dir={:#?}", ni);
-            let nj = j as i32 + dj;
-            // println!("nj dir={:#?}", nj);
+            let ni = i + di;
+            let nj = j + dj;
 
-            if ni < 3 && ni >= 0 && nj < 3 && nj >= 0 {
+            if (0..rows).contains(&ni) && (0..cols).contains(&nj) {
                 let mut new_board = Board::with_parent(self.cells.clone(), self);
-                let ni_new = ni * 3 + nj;
-                // println!("ni new={:#?}", ni_new);
+                let ni_new = ni * cols + nj;
                 // let f = new_board.manhatan_dist_not_first_time(&goal, i);
-                let f = new_board.manhatan_dist(&goal);
-                // println!("f={:#?}", f);
                 new_board.cells[og_i] = new_board.cells[ni_new as usize];
-                // println!("new_board={:?}", new_board);
                 new_board.cells[ni_new as usize] = 0;
-                // println!("new_board={:?}", new_board.cells);
-                // println!("new_board={:?}", new_board);
                 new_board.zero = ni_new as usize;
-                new_board.h_cost = f;
+                // h has to be taken after the swap, the parent's h overestimates
+                new_board.h_cost = new_board.manhatan_dist(goal);
                 new_board.g_cost = self.g_cost + 1;
 
                 v.push(new_board);
             }
         }
 
-        // println!("v={:?}", v);
         v
     }
+
+    // every push along the blank's row and column, each one move
+    fn get_pushes(&self, goal: &[i32]) -> Vec<Board> {
+        let (rows, cols) = (self.rows as i32, self.cols as i32);
+        let (i, j) = (self.zero as i32 / cols, self.zero as i32 % cols);
+        let mut v = Vec::new();
+        for &(di, dj) in &[(1, 0), (0, 1), (-1, 0), (0, -1)] {
+            let mut cells = self.cells.clone();
+            let mut blank = self.zero;
+            let (mut ni, mut nj) = (i + di, j + dj);
+            // each push that way is the one before it plus another tile
+            while (0..rows).contains(&ni) && (0..cols).contains(&nj) {
+                let next = (ni * cols + nj) as usize;
+                cells.swap(blank, next);
+                blank = next;
+
+                let mut new_board = Board::with_parent(cells.clone(), self);
+                new_board.h_cost = new_board.heuristic(goal, MoveMetric::MultiTile);
+                v.push(new_board);
+                (ni, nj) = (ni + di, nj + dj);
+            }
+        }
+        v
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells == Self::goal(self.rows, self.cols)
+    }
+
+    // slides the tile at idx into the blank, false if they aren't next to each other
+    pub fn slide_tile(&mut self, idx: usize) -> bool {
+        let zero = self.zero;
+        let cols = self.cols;
+        if idx >= self.cells.len()
+            || (idx / cols).abs_diff(zero / cols) + (idx % cols).abs_diff(zero % cols) != 1
+        {
+            return false;
+        }
+        self.cells.swap(idx, zero);
+        self.zero = idx;
+        true
+    }
+
+    // slides every tile between idx and the blank one step towards the blank, idx
+    // has to be in the blank's row or column. the no. of tiles that moved, None
+    // if idx isn't in line with the blank
+    pub fn slide_line(&mut self, idx: usize) -> Option<usize> {
+        let zero = self.zero;
+        let cols = self.cols;
+        if idx >= self.cells.len() || idx == zero {
+            return None;
+        }
+        let step = if idx / cols == zero / cols {
+            1
+        } else if idx % cols == zero % cols {
+            cols
+        } else {
+            return None;
+        };
+
+        let tiles = idx.abs_diff(zero) / step;
+        for _ in 0..tiles {
+            let next = if idx > self.zero {
+                self.zero + step
+            } else {
+                self.zero - step
+            };
+            self.cells.swap(self.zero, next);
+            self.zero = next;
+        }
+        Some(tiles)
+    }
+
+    // the cell rows/cols away from the blank, None when that's off the board
+    pub fn next_to_blank(&self, rows: isize, cols: isize) -> Option<usize> {
+        let row = (self.zero / self.cols) as isize + rows;
+        let col = (self.zero % self.cols) as isize + cols;
+        if row < 0 || col < 0 || row >= self.rows as isize || col >= self.cols as isize {
+            return None;
+        }
+        Some(row as usize * self.cols + col as usize)
+    }
+
+    // plays the slides in order, and how many moves they come to under metric.
+    // Err is the no. of the first one that isn't in line with the blank
+    pub fn replay(&self, slides: &[usize], metric: MoveMetric) -> Result<(Board, usize), usize> {
+        let mut board = self.clone();
+        let mut moves = 0;
+        for (no, &idx) in slides.iter().enumerate() {
+            let tiles = board.slide_line(idx).ok_or(no)?;
+            moves += metric.count(tiles);
+        }
+        Ok((board, moves))
+    }
+
+    // the whole way from this board to the goal, start first. None if there's
+    // no way or the solver ran out of budget looking for it. moves count under
+    // metric, so a multi tile path can be shorter
+    pub fn solve_with(&self, metric: MoveMetric) -> Option<Vec<Vec<i32>>> {
+        self.solve_reporting(metric, |_| {})
+    }
+
+    // same, with progress hearing how far it's got like solve_within's does
+    pub fn solve_reporting(
+        &self,
+        metric: MoveMetric,
+        progress: impl FnMut(usize),
+    ) -> Option<Vec<Vec<i32>>> {
+        let budget = BUDGET.load(AtomicOrdering::Relaxed);
+        self.solve_within(metric, budget, progress)
+    }
+
+    // same, but giving up after budget boards rather than the server's, for
+    // solves that run in the background. progress hears how many boards have
+    // been looked at every so often, and the total once it's done
+    pub fn solve_within(
+        &self,
+        metric: MoveMetric,
+        budget: usize,
+        mut progress: impl FnMut(usize),
+    ) -> Option<Vec<Vec<i32>>> {
+        let mut path = Self::a_star(self.clone(), metric, budget, &mut progress)?;
+        path.reverse();
+        // already solved comes back empty, the board itself is the whole path
+        if path.is_empty() {
+            path.push(self.cells.clone());
+        }
+        Some(path)
+    }
+
+    // no. of moves an optimal solution needs, None if there's no solution
+    pub fn optimal_distance(&self) -> Option<usize> {
+        self.optimal_distance_with(MoveMetric::SingleTile)
+    }
+
+    pub fn optimal_distance_with(&self, metric: MoveMetric) -> Option<usize> {
+        Some(self.solve_with(metric)?.len() - 1)
+    }
+
+    // the tile to slide into the blank next and the optimal no. of moves left,
+    // None if the board is already solved (or can't be). under the multi tile
+    // count the tile can be a whole push away, sliding it moves everything in
+    // between too
+    pub fn hint_with(&self, metric: MoveMetric) -> Option<(usize, usize)> {
+        let path = self.solve_with(metric)?;
+        let next = path.get(1)?;
+        Some((Self::find_zero(next), path.len() - 1))
+    }
+
     // return path as a vec for each step
     pub fn return_path(mut board: &Board) -> Vec<Vec<i32>> {
         let mut path = Vec::new();
@@ -300,16 +793,14 @@ impl Board {
 
         path.push(board.cells.clone());
 
-        // for (index, step) in path.iter().rev().enumerate() {
-        //     println!("h_cost {}", step.h_cost);
-        //     println!("g_cost {}", step.g_cost);
-        //     println!("Step {}:\n{}", index, step);
-        // }
-        // println!("path from fn={:?}", path);
         path
     }
 
-    fn print_path(mut board: &Board) {
+    // every step with its costs, only worked out when trace is on
+    fn trace_path(mut board: &Board) {
+        if !tracing::enabled!(tracing::Level::TRACE) {
+            return;
+        }
         let mut path = Vec::new();
 
         while let Some(parent) = &board.parent {
@@ -319,20 +810,14 @@ impl Board {
 
         path.push(board);
 
-        for (index, step) in path.iter().rev().enumerate() {
-            // println!("h_cost {}", step.h_cost);
-            // println!("g_cost {}", step.g_cost);
-            // println!("Step {}:\n{}", index, step);
+        for (step, board) in path.iter().rev().enumerate() {
+            tracing::trace!(
+                step,
+                h_cost = board.h_cost,
+                g_cost = board.g_cost,
+                cells = ?board.cells,
+                "path"
+            );
         }
     }
 }
-
-// fn main() {
-//     let start_cells = vec![1, 8, 3, 6, 4, 7, 5, 2, 0];
-//     let start_board = Board::new(start_cells.clone());
-//     let goal = vec![1, 2, 3, 4, 5, 6, 7, 8, 0];
-//     // println!("{:?}", Board::get_neighbors(&start_board, &goal));
-//     println!("{:?}", Board::sliding_puzzle_a_star(start_board));
-//     // let random_board = Board::random_board();
-//     // println!("{:?}", Board::sliding_puzzle_a_star(random_board));
-// }
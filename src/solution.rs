@@ -0,0 +1,28 @@
+use crate::cache::SolutionCache;
+use crate::session::Session;
+use crate::slayin::Board;
+use crate::store::Store;
+use crate::{current_board, current_game};
+
+// "solve from here": the way home from the board as the player left it, kept
+// as its own solution so the game and its history stay as they were. None if
+// the solver ran out of budget. a board anyone's solved before comes out of the cache
+pub async fn from_here(
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+) -> Option<Vec<Board>> {
+    let board = current_board(store, session).await;
+    let game = current_game(store, session).await;
+    let steps = solutions.solve(&board, game.move_metric()).await?;
+
+    store.add_solution(session, &game, &steps).await;
+
+    Some(steps)
+}
+
+// boards of the session's last solution, where it was solved from first. empty
+// when nothing's been solved yet
+pub async fn latest(store: &Store, session: &Session) -> Vec<Board> {
+    store.latest_solution(session).await
+}
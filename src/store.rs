@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::account::{PastGame, User};
+use crate::bests::Best;
+use crate::cache::{CachedPath, PathKey};
+use crate::daily::Solved;
+use crate::jobs::Job;
+use crate::leaderboard::{Entry, Filter, Score};
+use crate::race::{Race, RacePlayer};
+use crate::session::Session;
+use crate::slayin::{Board, MoveMetric};
+use crate::Game;
+
+mod memory;
+mod sqlite;
+
+pub use memory::MemoryStore;
+pub use sqlite::SqliteStore;
+
+// what the handlers hold, whichever backend the config picked
+pub(crate) type Store = Arc<dyn GameStore>;
+
+// everything the game keeps between requests. the handlers decide what happens,
+// a store only keeps it, so both backends behave the same
+#[async_trait]
+pub(crate) trait GameStore: Send + Sync {
+    // false once the store can't be read from, /readyz asks
+    async fn ping(&self) -> bool;
+    // lets go of the connections on shutdown, nothing works after it
+    async fn close(&self);
+
+    // the board the session is playing on, None before its first game
+    async fn board(&self, session: &Session) -> Option<Board>;
+    async fn set_board(&self, session: &Session, board: &Board);
+
+    async fn game(&self, session: &Session) -> Option<Game>;
+    // wipes the session's old board, game and solutions and starts board
+    async fn start_game(
+        &self,
+        session: &Session,
+        board: &Board,
+        distance: i32,
+        metric: MoveMetric,
+    ) -> Game;
+    // a slide of the tile at idx counted as moves, solved_ms when it's the one
+    // that solves the game
+    async fn record_move(
+        &self,
+        game_id: i64,
+        idx: usize,
+        moves: i32,
+        distance: i32,
+        solved_ms: Option<i64>,
+    );
+    async fn set_metric(&self, game_id: i64, metric: MoveMetric, distance: i32);
+    // makes the game the session's go at date. its clock runs from the
+    // session's first go at the day, however many times it's been started
+    async fn set_daily(&self, game_id: i64, date: &str);
+
+    async fn add_solution(&self, session: &Session, game: &Game, steps: &[Board]);
+    // the steps of the session's last solution, empty when there isn't one
+    async fn latest_solution(&self, session: &Session) -> Vec<Board>;
+    // counts as a use, so it's the last to get evicted
+    async fn cached_path(&self, key: &PathKey) -> Option<CachedPath>;
+    // keeps at most keep paths, handing back how many got evicted to fit this one
+    async fn cache_path(&self, path: &CachedPath, keep: usize) -> usize;
+
+    // called with a game that's just been solved
+    async fn add_result(&self, session: &Session, game: &Game);
+    async fn bests(&self, session: &Session) -> Vec<Best>;
+    // the best for games like this one, leaving this one out
+    async fn best_before(&self, session: &Session, game: &Game) -> Option<Best>;
+    async fn daily_result(&self, session: &Session, date: &str) -> Option<Solved>;
+
+    async fn scores(&self, filter: &Filter, limit: usize) -> Vec<Entry>;
+    async fn daily_scores(&self, date: &str, limit: usize) -> Vec<Entry>;
+    // false when the game, or the session's go at the day, is already on there
+    async fn add_score(&self, score: &Score) -> bool;
+
+    async fn add_picture(&self, session: &Session, token: &str);
+    async fn clear_pictures(&self, session: &Session);
+    async fn active_picture(&self, session: &Session) -> Option<String>;
+
+    // false when the code's taken
+    async fn add_race(&self, code: &str, seed: i64, board: &Board, distance: i32) -> bool;
+    async fn race(&self, code: &str) -> Option<Race>;
+    async fn race_players(&self, race_id: i64) -> Vec<RacePlayer>;
+    async fn seat(&self, race_id: i64, session: &Session) -> Option<i32>;
+    // false when the seat or the session is already in the race
+    async fn add_player(&self, race_id: i64, seat: i32, session: &Session, board: &Board) -> bool;
+    async fn update_player(&self, race_id: i64, seat: i32, board: &Board, finished: bool);
+    // only the first one to finish gets it
+    async fn set_winner(&self, race_id: i64, seat: i32);
+
+    // queues a solve of board, handing back its id
+    async fn add_job(
+        &self,
+        session: &Session,
+        board: &Board,
+        metric: MoveMetric,
+        budget: usize,
+    ) -> i64;
+    async fn job(&self, id: i64) -> Option<Job>;
+    // the oldest queued job, marked running so no other worker takes it
+    async fn claim_job(&self) -> Option<Job>;
+    async fn job_progress(&self, id: i64, nodes: usize);
+    // done with the path when there is one, gave_up without
+    async fn finish_job(&self, id: i64, nodes: usize, path: Option<&[Board]>);
+    // jobs left running when the server stopped go back in the queue, handing
+    // back how many
+    async fn requeue_jobs(&self) -> usize;
+
+    // None when someone already has the name, whatever its case
+    async fn add_user(&self, name: &str, password_hash: &str) -> Option<i64>;
+    async fn user(&self, name: &str) -> Option<User>;
+    // logins are kept by session::token_hash of the cookie's token, never the
+    // token itself. ones past their expires_ms get cleared out along the way
+    async fn add_login(&self, token_hash: &str, user_id: i64, expires_ms: i64);
+    // whose the account cookie's token is, None once they've logged out or
+    // the login's expired
+    async fn login(&self, token_hash: &str) -> Option<User>;
+    async fn remove_login(&self, token_hash: &str);
+    // moves what from has played over to into. from's game in progress takes
+    // over from into's, see account::merge
+    async fn merge_sessions(&self, from: &Session, into: &Session);
+    // solved games, the latest first
+    async fn past_games(&self, session: &Session, limit: usize) -> Vec<PastGame>;
+    // one of them, None when it's someone else's
+    async fn past_game(&self, session: &Session, id: i64) -> Option<PastGame>;
+}
+
+// utc "2026-10-19 14:03:00", what sqlite's CURRENT_TIMESTAMP gives
+pub(crate) fn now() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    timestamp(secs)
+}
+
+// seconds since 1970 as a CURRENT_TIMESTAMP, the days go to a date the way
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days does
+pub(crate) fn timestamp(secs: i64) -> String {
+    let (days, secs) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
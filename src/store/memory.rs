@@ -0,0 +1,762 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+
+use super::{now, GameStore};
+use crate::account::{PastGame, User};
+use crate::bests::{difficulty, Best};
+use crate::cache::{CachedPath, PathKey};
+use crate::daily::Solved;
+use crate::jobs::{Job, DONE, GAVE_UP, QUEUED, RUNNING};
+use crate::leaderboard::{Entry, Filter, Metric, Period, Score};
+use crate::race::{Race, RacePlayer};
+use crate::session::Session;
+use crate::slayin::{Board, MoveMetric};
+use crate::{format_board, now_ms, Game};
+
+// everything in the process, gone when it stops. for trying the server out
+// and for tests that don't want a database file
+#[derive(Clone, Default)]
+pub struct MemoryStore(Arc<RwLock<Tables>>);
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+}
+
+// the sqlite tables, keyed the way they get looked up
+#[derive(Default)]
+struct Tables {
+    boards: HashMap<String, Board>,
+    games: HashMap<String, Game>,
+    games_started: i64,
+    // only the latest one of each session ever gets read back
+    solutions: HashMap<String, Vec<Board>>,
+    // with the use it was last looked up on, the lowest goes first
+    paths: HashMap<PathKey, (CachedPath, u64)>,
+    path_uses: u64,
+    results: Vec<Record>,
+    // (session, date) to when the session first started the day's puzzle
+    daily_starts: HashMap<(String, String), i64>,
+    scores: Vec<(i64, Score, String)>,
+    pictures: Vec<Picture>,
+    races: Vec<Race>,
+    players: HashMap<i64, Vec<Player>>,
+    // oldest first, ids count up from 1 like sqlite's
+    jobs: Vec<Job>,
+    users: Vec<User>,
+    // token hash to user id and when it expires
+    logins: HashMap<String, (i64, i64)>,
+}
+
+struct Record {
+    id: i64,
+    session: String,
+    game_id: i64,
+    rows: i32,
+    cols: i32,
+    difficulty: &'static str,
+    metric: String,
+    optimal: i32,
+    moves: i32,
+    elapsed_ms: i64,
+    daily: Option<String>,
+    solved_at: String,
+    start_board: String,
+    history: String,
+}
+
+struct Picture {
+    token: String,
+    session: String,
+    active: bool,
+}
+
+struct Player {
+    session: String,
+    seat: i32,
+    board: Board,
+    moves: i32,
+    finished: bool,
+}
+
+impl Tables {
+    fn game_mut(&mut self, game_id: i64) -> Option<&mut Game> {
+        self.games.values_mut().find(|game| game.id == game_id)
+    }
+
+    fn race_mut(&mut self, race_id: i64) -> Option<&mut Race> {
+        self.races.iter_mut().find(|race| race.id == race_id)
+    }
+
+    fn job_mut(&mut self, id: i64) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+}
+
+// results grouped up into a best, the same as the GROUP BY in the sqlite store
+fn best<'a>(results: impl Iterator<Item = &'a Record>) -> Option<Best> {
+    results.fold(None, |best: Option<Best>, result| {
+        Some(match best {
+            None => Best {
+                rows: result.rows,
+                cols: result.cols,
+                difficulty: result.difficulty.to_owned(),
+                metric: result.metric.clone(),
+                moves: result.moves,
+                elapsed_ms: result.elapsed_ms,
+                solved: 1,
+            },
+            Some(best) => Best {
+                moves: best.moves.min(result.moves),
+                elapsed_ms: best.elapsed_ms.min(result.elapsed_ms),
+                solved: best.solved + 1,
+                ..best
+            },
+        })
+    })
+}
+
+fn past_game(result: &Record) -> PastGame {
+    PastGame {
+        id: result.id,
+        rows: result.rows,
+        cols: result.cols,
+        difficulty: result.difficulty.to_owned(),
+        metric: result.metric.clone(),
+        optimal: result.optimal,
+        moves: result.moves,
+        elapsed_ms: result.elapsed_ms,
+        daily: result.daily.clone(),
+        solved_at: result.solved_at.clone(),
+        start_board: result.start_board.clone(),
+        history: result.history.clone(),
+    }
+}
+
+fn entry(score: &Score, created_at: &str) -> Entry {
+    Entry {
+        name: score.name.clone(),
+        moves: score.moves,
+        optimal: score.optimal,
+        elapsed_ms: score.elapsed_ms,
+        efficiency: score.efficiency,
+        created_at: created_at.to_owned(),
+    }
+}
+
+#[async_trait]
+impl GameStore for MemoryStore {
+    // nothing to connect to, it's there as long as the server is
+    async fn ping(&self) -> bool {
+        true
+    }
+
+    async fn close(&self) {}
+
+    async fn board(&self, session: &Session) -> Option<Board> {
+        self.0.read().unwrap().boards.get(&session.0).cloned()
+    }
+
+    async fn set_board(&self, session: &Session, board: &Board) {
+        let mut tables = self.0.write().unwrap();
+        if let Some(current) = tables.boards.get_mut(&session.0) {
+            *current = board.clone();
+        }
+    }
+
+    async fn game(&self, session: &Session) -> Option<Game> {
+        self.0.read().unwrap().games.get(&session.0).cloned()
+    }
+
+    async fn start_game(
+        &self,
+        session: &Session,
+        board: &Board,
+        distance: i32,
+        metric: MoveMetric,
+    ) -> Game {
+        let mut tables = self.0.write().unwrap();
+        tables.games_started += 1;
+        let game = Game {
+            id: tables.games_started,
+            start_distance: distance,
+            distance,
+            moves: 0,
+            rows: board.rows as i32,
+            cols: board.cols as i32,
+            started_ms: now_ms(),
+            solved_ms: None,
+            start_board: format_board(board),
+            history: String::new(),
+            daily: None,
+            solved_moves: None,
+            metric: metric.as_str().to_owned(),
+        };
+
+        tables.boards.insert(session.0.clone(), board.clone());
+        tables.solutions.remove(&session.0);
+        tables.games.insert(session.0.clone(), game.clone());
+        game
+    }
+
+    async fn record_move(
+        &self,
+        game_id: i64,
+        idx: usize,
+        moves: i32,
+        distance: i32,
+        solved_ms: Option<i64>,
+    ) {
+        let mut tables = self.0.write().unwrap();
+        let Some(game) = tables.game_mut(game_id) else {
+            return;
+        };
+        game.distance = distance;
+        game.moves += moves;
+        game.history = format!("{} {}", game.history, idx).trim_start().to_owned();
+        if solved_ms.is_some() {
+            game.solved_ms = solved_ms;
+            game.solved_moves = Some(game.moves);
+        }
+    }
+
+    async fn set_metric(&self, game_id: i64, metric: MoveMetric, distance: i32) {
+        let mut tables = self.0.write().unwrap();
+        if let Some(game) = tables.game_mut(game_id) {
+            game.metric = metric.as_str().to_owned();
+            game.start_distance = distance;
+            game.distance = distance;
+        }
+    }
+
+    async fn set_daily(&self, game_id: i64, date: &str) {
+        let mut tables = self.0.write().unwrap();
+        let tables = &mut *tables;
+        let Some((session, game)) = tables.games.iter_mut().find(|(_, game)| game.id == game_id)
+        else {
+            return;
+        };
+        let key = (session.clone(), date.to_owned());
+        game.started_ms = *tables.daily_starts.entry(key).or_insert(game.started_ms);
+        game.daily = Some(date.to_owned());
+    }
+
+    async fn add_solution(&self, session: &Session, _game: &Game, steps: &[Board]) {
+        let mut tables = self.0.write().unwrap();
+        tables.solutions.insert(session.0.clone(), steps.to_vec());
+    }
+
+    async fn latest_solution(&self, session: &Session) -> Vec<Board> {
+        let tables = self.0.read().unwrap();
+        tables
+            .solutions
+            .get(&session.0)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn cached_path(&self, key: &PathKey) -> Option<CachedPath> {
+        let mut tables = self.0.write().unwrap();
+        tables.path_uses += 1;
+        let uses = tables.path_uses;
+        let (path, used) = tables.paths.get_mut(key)?;
+        *used = uses;
+        Some(path.clone())
+    }
+
+    async fn cache_path(&self, path: &CachedPath, keep: usize) -> usize {
+        let mut tables = self.0.write().unwrap();
+        tables.path_uses += 1;
+        let uses = tables.path_uses;
+        tables.paths.insert(path.key.clone(), (path.clone(), uses));
+
+        let mut evicted = 0;
+        while tables.paths.len() > keep {
+            let oldest = tables
+                .paths
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone())
+                .unwrap();
+            tables.paths.remove(&oldest);
+            evicted += 1;
+        }
+        evicted
+    }
+
+    async fn add_result(&self, session: &Session, game: &Game) {
+        let mut tables = self.0.write().unwrap();
+        let id = tables.results.len() as i64 + 1;
+        tables.results.push(Record {
+            id,
+            session: session.0.clone(),
+            game_id: game.id,
+            rows: game.rows,
+            cols: game.cols,
+            difficulty: difficulty(game.start_distance),
+            metric: game.metric.clone(),
+            optimal: game.start_distance,
+            moves: game.solved_moves.unwrap_or(game.moves),
+            elapsed_ms: game.elapsed_ms(),
+            daily: game.daily.clone(),
+            solved_at: now(),
+            start_board: game.start_board.clone(),
+            history: game.history.clone(),
+        });
+    }
+
+    async fn bests(&self, session: &Session) -> Vec<Best> {
+        let tables = self.0.read().unwrap();
+        let mut grouped = BTreeMap::new();
+        for result in tables
+            .results
+            .iter()
+            .filter(|result| result.session == session.0)
+        {
+            let rank = match result.difficulty {
+                "easy" => 0,
+                "medium" => 1,
+                _ => 2,
+            };
+            grouped
+                .entry((
+                    result.rows * result.cols,
+                    result.rows,
+                    rank,
+                    result.metric.clone(),
+                ))
+                .or_insert_with(Vec::new)
+                .push(result);
+        }
+        grouped
+            .into_values()
+            .filter_map(|results| best(results.into_iter()))
+            .collect()
+    }
+
+    async fn best_before(&self, session: &Session, game: &Game) -> Option<Best> {
+        let tables = self.0.read().unwrap();
+        best(tables.results.iter().filter(|result| {
+            result.session == session.0
+                && result.rows == game.rows
+                && result.cols == game.cols
+                && result.difficulty == difficulty(game.start_distance)
+                && result.metric == game.metric
+                && result.game_id != game.id
+        }))
+    }
+
+    async fn daily_result(&self, session: &Session, date: &str) -> Option<Solved> {
+        let tables = self.0.read().unwrap();
+        tables
+            .results
+            .iter()
+            .find(|result| result.session == session.0 && result.daily.as_deref() == Some(date))
+            .map(|result| Solved {
+                moves: result.moves,
+                optimal: result.optimal,
+                elapsed_ms: result.elapsed_ms,
+            })
+    }
+
+    async fn scores(&self, filter: &Filter, limit: usize) -> Vec<Entry> {
+        let tables = self.0.read().unwrap();
+        let now = now();
+        let week_ago = super::timestamp(now_ms() / 1000 - 7 * 24 * 60 * 60);
+        let mut scores: Vec<_> = tables
+            .scores
+            .iter()
+            .filter(|(_, score, created_at)| {
+                score.rows == filter.rows
+                    && score.cols == filter.cols
+                    && score.difficulty == filter.difficulty
+                    && score.metric == filter.move_metric
+                    && match filter.period {
+                        Period::Daily => created_at[..10] == now[..10],
+                        Period::Weekly => *created_at >= week_ago,
+                        Period::AllTime => true,
+                    }
+            })
+            .collect();
+        scores.sort_by_key(|(id, score, _)| match filter.metric {
+            Metric::Time => (score.elapsed_ms, score.moves as i64, *id),
+            Metric::Moves => (score.moves as i64, score.elapsed_ms, *id),
+            Metric::Efficiency => (-score.efficiency as i64, score.elapsed_ms, *id),
+        });
+        scores
+            .into_iter()
+            .take(limit)
+            .map(|(_, score, created_at)| entry(score, created_at))
+            .collect()
+    }
+
+    async fn daily_scores(&self, date: &str, limit: usize) -> Vec<Entry> {
+        let tables = self.0.read().unwrap();
+        let mut scores: Vec<_> = tables
+            .scores
+            .iter()
+            .filter(|(_, score, _)| score.daily.as_deref() == Some(date))
+            .collect();
+        scores.sort_by_key(|(id, score, _)| (score.moves, score.elapsed_ms, *id));
+        scores
+            .into_iter()
+            .take(limit)
+            .map(|(_, score, created_at)| entry(score, created_at))
+            .collect()
+    }
+
+    async fn add_score(&self, score: &Score) -> bool {
+        let mut tables = self.0.write().unwrap();
+        let taken = tables.scores.iter().any(|(_, kept, _)| {
+            kept.game_id == score.game_id
+                || (kept.daily.is_some()
+                    && kept.daily == score.daily
+                    && kept.session == score.session)
+        });
+        if taken {
+            return false;
+        }
+        let id = tables.scores.len() as i64 + 1;
+        tables.scores.push((id, score.clone(), now()));
+        true
+    }
+
+    async fn add_picture(&self, session: &Session, token: &str) {
+        let mut tables = self.0.write().unwrap();
+        tables.pictures.push(Picture {
+            token: token.to_owned(),
+            session: session.0.clone(),
+            active: true,
+        });
+    }
+
+    async fn clear_pictures(&self, session: &Session) {
+        let mut tables = self.0.write().unwrap();
+        for picture in tables
+            .pictures
+            .iter_mut()
+            .filter(|picture| picture.session == session.0)
+        {
+            picture.active = false;
+        }
+    }
+
+    async fn active_picture(&self, session: &Session) -> Option<String> {
+        let tables = self.0.read().unwrap();
+        tables
+            .pictures
+            .iter()
+            .rev()
+            .find(|picture| picture.session == session.0 && picture.active)
+            .map(|picture| picture.token.clone())
+    }
+
+    async fn add_race(&self, code: &str, _seed: i64, board: &Board, distance: i32) -> bool {
+        let mut tables = self.0.write().unwrap();
+        if tables.races.iter().any(|race| race.code == code) {
+            return false;
+        }
+        let id = tables.races.len() as i64 + 1;
+        tables.races.push(Race {
+            id,
+            code: code.to_owned(),
+            start_board: format_board(board),
+            distance,
+            winner: None,
+        });
+        true
+    }
+
+    async fn race(&self, code: &str) -> Option<Race> {
+        let tables = self.0.read().unwrap();
+        tables.races.iter().find(|race| race.code == code).cloned()
+    }
+
+    async fn race_players(&self, race_id: i64) -> Vec<RacePlayer> {
+        let tables = self.0.read().unwrap();
+        let mut players: Vec<RacePlayer> = tables
+            .players
+            .get(&race_id)
+            .into_iter()
+            .flatten()
+            .map(|player| RacePlayer {
+                seat: player.seat,
+                board: format_board(&player.board),
+                moves: player.moves,
+                finished: player.finished,
+            })
+            .collect();
+        players.sort_by_key(|player| player.seat);
+        players
+    }
+
+    async fn seat(&self, race_id: i64, session: &Session) -> Option<i32> {
+        let tables = self.0.read().unwrap();
+        tables
+            .players
+            .get(&race_id)?
+            .iter()
+            .find(|player| player.session == session.0)
+            .map(|player| player.seat)
+    }
+
+    async fn add_player(&self, race_id: i64, seat: i32, session: &Session, board: &Board) -> bool {
+        let mut tables = self.0.write().unwrap();
+        let players = tables.players.entry(race_id).or_default();
+        if players
+            .iter()
+            .any(|player| player.seat == seat || player.session == session.0)
+        {
+            return false;
+        }
+        players.push(Player {
+            session: session.0.clone(),
+            seat,
+            board: board.clone(),
+            moves: 0,
+            finished: false,
+        });
+        true
+    }
+
+    async fn update_player(&self, race_id: i64, seat: i32, board: &Board, finished: bool) {
+        let mut tables = self.0.write().unwrap();
+        let player = tables
+            .players
+            .get_mut(&race_id)
+            .and_then(|players| players.iter_mut().find(|player| player.seat == seat));
+        if let Some(player) = player {
+            player.board = board.clone();
+            player.moves += 1;
+            player.finished = finished;
+        }
+    }
+
+    async fn set_winner(&self, race_id: i64, seat: i32) {
+        let mut tables = self.0.write().unwrap();
+        if let Some(race) = tables.race_mut(race_id) {
+            race.winner.get_or_insert(seat);
+        }
+    }
+
+    async fn add_job(
+        &self,
+        session: &Session,
+        board: &Board,
+        metric: MoveMetric,
+        budget: usize,
+    ) -> i64 {
+        let mut tables = self.0.write().unwrap();
+        let id = tables.jobs.len() as i64 + 1;
+        tables.jobs.push(Job {
+            id,
+            session: session.0.clone(),
+            board: board.code(),
+            metric: metric.as_str().to_owned(),
+            budget: budget as i64,
+            status: QUEUED.to_owned(),
+            nodes: 0,
+            path: None,
+            created_at: now(),
+            finished_at: None,
+        });
+        id
+    }
+
+    async fn job(&self, id: i64) -> Option<Job> {
+        let tables = self.0.read().unwrap();
+        tables.jobs.iter().find(|job| job.id == id).cloned()
+    }
+
+    async fn claim_job(&self) -> Option<Job> {
+        let mut tables = self.0.write().unwrap();
+        let job = tables.jobs.iter_mut().find(|job| job.status == QUEUED)?;
+        job.status = RUNNING.to_owned();
+        Some(job.clone())
+    }
+
+    async fn job_progress(&self, id: i64, nodes: usize) {
+        if let Some(job) = self.0.write().unwrap().job_mut(id) {
+            job.nodes = nodes as i64;
+        }
+    }
+
+    async fn finish_job(&self, id: i64, nodes: usize, path: Option<&[Board]>) {
+        if let Some(job) = self.0.write().unwrap().job_mut(id) {
+            job.status = if path.is_some() { DONE } else { GAVE_UP }.to_owned();
+            job.nodes = nodes as i64;
+            job.path = path.map(|steps| {
+                steps
+                    .iter()
+                    .map(|step| step.code())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            });
+            job.finished_at = Some(now());
+        }
+    }
+
+    // nothing outlives a restart here, so there's never anything to pick back up
+    async fn requeue_jobs(&self) -> usize {
+        let mut tables = self.0.write().unwrap();
+        let running = tables.jobs.iter_mut().filter(|job| job.status == RUNNING);
+        running.map(|job| job.status = QUEUED.to_owned()).count()
+    }
+
+    // names compare the way sqlite's NOCASE does, ascii only
+    async fn add_user(&self, name: &str, password_hash: &str) -> Option<i64> {
+        let mut tables = self.0.write().unwrap();
+        if tables
+            .users
+            .iter()
+            .any(|user| user.name.eq_ignore_ascii_case(name))
+        {
+            return None;
+        }
+        let id = tables.users.len() as i64 + 1;
+        tables.users.push(User {
+            id,
+            name: name.to_owned(),
+            password_hash: password_hash.to_owned(),
+            created_at: now(),
+        });
+        Some(id)
+    }
+
+    async fn user(&self, name: &str) -> Option<User> {
+        let tables = self.0.read().unwrap();
+        tables
+            .users
+            .iter()
+            .find(|user| user.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    async fn add_login(&self, token_hash: &str, user_id: i64, expires_ms: i64) {
+        let mut tables = self.0.write().unwrap();
+        let now = now_ms();
+        tables.logins.retain(|_, (_, expires_ms)| *expires_ms > now);
+        tables
+            .logins
+            .insert(token_hash.to_owned(), (user_id, expires_ms));
+    }
+
+    async fn login(&self, token_hash: &str) -> Option<User> {
+        let tables = self.0.read().unwrap();
+        let (user_id, expires_ms) = tables.logins.get(token_hash)?;
+        if *expires_ms <= now_ms() {
+            return None;
+        }
+        tables
+            .users
+            .iter()
+            .find(|user| user.id == *user_id)
+            .cloned()
+    }
+
+    async fn remove_login(&self, token_hash: &str) {
+        self.0.write().unwrap().logins.remove(token_hash);
+    }
+
+    async fn merge_sessions(&self, from: &Session, into: &Session) {
+        let mut tables = self.0.write().unwrap();
+        let tables = &mut *tables;
+
+        if let Some(board) = tables.boards.remove(&from.0) {
+            tables.boards.insert(into.0.clone(), board);
+            tables.solutions.remove(&into.0);
+            tables.games.remove(&into.0);
+            if let Some(game) = tables.games.remove(&from.0) {
+                tables.games.insert(into.0.clone(), game);
+            }
+            if let Some(steps) = tables.solutions.remove(&from.0) {
+                tables.solutions.insert(into.0.clone(), steps);
+            }
+        }
+
+        let showing = tables
+            .pictures
+            .iter()
+            .any(|picture| picture.session == into.0 && picture.active);
+        for picture in tables
+            .pictures
+            .iter_mut()
+            .filter(|picture| picture.session == from.0)
+        {
+            picture.session = into.0.clone();
+            picture.active &= !showing;
+        }
+
+        for result in tables
+            .results
+            .iter_mut()
+            .filter(|result| result.session == from.0)
+        {
+            result.session = into.0.clone();
+        }
+
+        let days: Vec<String> = tables
+            .scores
+            .iter()
+            .filter(|(_, score, _)| score.session == into.0)
+            .filter_map(|(_, score, _)| score.daily.clone())
+            .collect();
+        for (_, score, _) in tables.scores.iter_mut().filter(|(_, score, _)| {
+            score.session == from.0 && score.daily.as_ref().is_none_or(|day| !days.contains(day))
+        }) {
+            score.session = into.0.clone();
+        }
+
+        // the account's own first go at a day stays the one its clock runs from
+        let started: Vec<(String, String)> = tables
+            .daily_starts
+            .keys()
+            .filter(|(session, _)| *session == from.0)
+            .cloned()
+            .collect();
+        for key in started {
+            let started_ms = tables.daily_starts.remove(&key).unwrap();
+            tables
+                .daily_starts
+                .entry((into.0.clone(), key.1))
+                .or_insert(started_ms);
+        }
+
+        for players in tables.players.values_mut() {
+            if players.iter().any(|player| player.session == into.0) {
+                continue;
+            }
+            if let Some(player) = players.iter_mut().find(|player| player.session == from.0) {
+                player.session = into.0.clone();
+            }
+        }
+
+        for job in tables.jobs.iter_mut().filter(|job| job.session == from.0) {
+            job.session = into.0.clone();
+        }
+    }
+
+    async fn past_games(&self, session: &Session, limit: usize) -> Vec<PastGame> {
+        let tables = self.0.read().unwrap();
+        tables
+            .results
+            .iter()
+            .rev()
+            .filter(|result| result.session == session.0)
+            .take(limit)
+            .map(past_game)
+            .collect()
+    }
+
+    async fn past_game(&self, session: &Session, id: i64) -> Option<PastGame> {
+        let tables = self.0.read().unwrap();
+        tables
+            .results
+            .iter()
+            .find(|result| result.id == id && result.session == session.0)
+            .map(past_game)
+    }
+}
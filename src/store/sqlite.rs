@@ -0,0 +1,758 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use super::GameStore;
+use crate::account::{PastGame, User};
+use crate::bests::{difficulty, Best};
+use crate::cache::{CachedPath, PathKey};
+use crate::daily::Solved;
+use crate::jobs::{Job, DONE, GAVE_UP};
+use crate::leaderboard::{Entry, Filter, Metric, Period, Score};
+use crate::race::{Race, RacePlayer};
+use crate::session::Session;
+use crate::slayin::{Board, MoveMetric};
+use crate::{format_board, now_ms, parse_board, Game};
+
+// the database the server has always run on
+#[derive(Clone)]
+pub struct SqliteStore(SqlitePool);
+
+impl SqliteStore {
+    pub fn new(pool: SqlitePool) -> SqliteStore {
+        SqliteStore(pool)
+    }
+}
+
+#[async_trait]
+impl GameStore for SqliteStore {
+    async fn ping(&self) -> bool {
+        !self.0.is_closed() && sqlx::query("SELECT 1").execute(&self.0).await.is_ok()
+    }
+
+    async fn close(&self) {
+        self.0.close().await;
+    }
+
+    // row 0 of full_path gets updated by every slide
+    async fn board(&self, session: &Session) -> Option<Board> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT path_json FROM full_path WHERE iteration_index = 0 AND session = ?",
+        )
+        .bind(&session.0)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .pop()
+        .map(|board| parse_board(&board))
+    }
+
+    async fn set_board(&self, session: &Session, board: &Board) {
+        sqlx::query("UPDATE full_path SET path_json = ? WHERE iteration_index = 0 AND session = ?")
+            .bind(format_board(board))
+            .bind(&session.0)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn game(&self, session: &Session) -> Option<Game> {
+        sqlx::query_as::<_, Game>("SELECT * FROM games WHERE session = ? ORDER BY id DESC LIMIT 1")
+            .bind(&session.0)
+            .fetch_optional(&self.0)
+            .await
+            .unwrap()
+    }
+
+    async fn start_game(
+        &self,
+        session: &Session,
+        board: &Board,
+        distance: i32,
+        metric: MoveMetric,
+    ) -> Game {
+        // all or nothing, a half started game would leave the session with a
+        // board and no game or the old game on the new board
+        let mut tx = self.0.begin().await.unwrap();
+        for clear in [
+            "DELETE FROM full_path WHERE session = ?",
+            "DELETE FROM solution_steps
+             WHERE solution_id IN (SELECT id FROM solutions WHERE session = ?)",
+            "DELETE FROM solutions WHERE session = ?",
+            "DELETE FROM games WHERE session = ?",
+        ] {
+            sqlx::query(clear)
+                .bind(&session.0)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO full_path (path_json, iteration_index, session) VALUES (?, ?, ?)")
+            .bind(format_board(board))
+            .bind(0)
+            .bind(&session.0)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO games (start_distance, distance, session, rows, cols, started_ms,
+                                start_board, metric)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(distance)
+        .bind(distance)
+        .bind(&session.0)
+        .bind(board.rows as i32)
+        .bind(board.cols as i32)
+        .bind(now_ms())
+        .bind(format_board(board))
+        .bind(metric.as_str())
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        self.game(session).await.unwrap()
+    }
+
+    // no RETURNING, sqlite only commits once the statement is stepped to the end
+    // and fetch_one stops after the first row
+    async fn record_move(
+        &self,
+        game_id: i64,
+        idx: usize,
+        moves: i32,
+        distance: i32,
+        solved_ms: Option<i64>,
+    ) {
+        sqlx::query(
+            "UPDATE games SET distance = ?1, moves = moves + ?5,
+                 history = LTRIM(history || ' ' || ?2),
+                 solved_ms = COALESCE(?3, solved_ms),
+                 solved_moves = CASE WHEN ?3 IS NOT NULL THEN moves + ?5 ELSE solved_moves END
+             WHERE id = ?4",
+        )
+        .bind(distance)
+        .bind(idx as i64)
+        .bind(solved_ms)
+        .bind(game_id)
+        .bind(moves)
+        .execute(&self.0)
+        .await
+        .unwrap();
+    }
+
+    async fn set_metric(&self, game_id: i64, metric: MoveMetric, distance: i32) {
+        sqlx::query("UPDATE games SET metric = ?, start_distance = ?, distance = ? WHERE id = ?")
+            .bind(metric.as_str())
+            .bind(distance)
+            .bind(distance)
+            .bind(game_id)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn set_daily(&self, game_id: i64, date: &str) {
+        let mut tx = self.0.begin().await.unwrap();
+        for query in [
+            "INSERT OR IGNORE INTO daily_starts (session, daily, started_ms)
+             SELECT session, ?1, started_ms FROM games WHERE id = ?2",
+            "UPDATE games SET daily = ?1,
+                 started_ms = (SELECT started_ms FROM daily_starts
+                               WHERE session = games.session AND daily = ?1)
+             WHERE id = ?2",
+        ] {
+            sqlx::query(query)
+                .bind(date)
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        tx.commit().await.unwrap();
+    }
+
+    async fn add_solution(&self, session: &Session, game: &Game, steps: &[Board]) {
+        // latest_solution never sees one with only some of its steps in
+        let mut tx = self.0.begin().await.unwrap();
+        let id =
+            sqlx::query("INSERT INTO solutions (session, game_id, from_move) VALUES (?, ?, ?)")
+                .bind(&session.0)
+                .bind(game.id)
+                .bind(game.moves)
+                .execute(&mut *tx)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+        for (step, board) in steps.iter().enumerate() {
+            sqlx::query("INSERT INTO solution_steps (solution_id, step, board) VALUES (?, ?, ?)")
+                .bind(id)
+                .bind(step as i64)
+                .bind(format_board(board))
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+        tx.commit().await.unwrap();
+    }
+
+    async fn latest_solution(&self, session: &Session) -> Vec<Board> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT board FROM solution_steps
+             WHERE solution_id = (SELECT MAX(id) FROM solutions WHERE session = ?)
+             ORDER BY step",
+        )
+        .bind(&session.0)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .iter()
+        .map(|board| parse_board(board))
+        .collect()
+    }
+
+    async fn cached_path(&self, key: &PathKey) -> Option<CachedPath> {
+        let (path, algorithm, optimal) = sqlx::query_as::<_, (String, String, bool)>(
+            "SELECT path, algorithm, optimal FROM solution_cache
+             WHERE board = ? AND goal = ? AND metric = ?",
+        )
+        .bind(&key.board)
+        .bind(&key.goal)
+        .bind(key.metric.as_str())
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .pop()?;
+        sqlx::query(
+            "UPDATE solution_cache SET hits = hits + 1, used_ms = ?
+             WHERE board = ? AND goal = ? AND metric = ?",
+        )
+        .bind(now_ms())
+        .bind(&key.board)
+        .bind(&key.goal)
+        .bind(key.metric.as_str())
+        .execute(&self.0)
+        .await
+        .unwrap();
+
+        Some(CachedPath {
+            key: key.clone(),
+            steps: path
+                .split_whitespace()
+                .map(|code| Board::from_code(code).unwrap())
+                .collect(),
+            algorithm,
+            optimal,
+        })
+    }
+
+    async fn cache_path(&self, path: &CachedPath, keep: usize) -> usize {
+        sqlx::query(
+            "INSERT OR REPLACE INTO solution_cache
+                 (board, goal, metric, path, algorithm, optimal, used_ms)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&path.key.board)
+        .bind(&path.key.goal)
+        .bind(path.key.metric.as_str())
+        .bind(
+            path.steps
+                .iter()
+                .map(Board::code)
+                .collect::<Vec<String>>()
+                .join(" "),
+        )
+        .bind(&path.algorithm)
+        .bind(path.optimal)
+        .bind(now_ms())
+        .execute(&self.0)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "DELETE FROM solution_cache WHERE rowid IN
+                 (SELECT rowid FROM solution_cache ORDER BY used_ms DESC, rowid DESC
+                  LIMIT -1 OFFSET ?)",
+        )
+        .bind(keep as i64)
+        .execute(&self.0)
+        .await
+        .unwrap()
+        .rows_affected() as usize
+    }
+
+    async fn add_result(&self, session: &Session, game: &Game) {
+        sqlx::query(
+            "INSERT INTO results (session, game_id, rows, cols, difficulty, optimal, moves,
+                                  elapsed_ms, daily, metric, start_board, history)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&session.0)
+        .bind(game.id)
+        .bind(game.rows)
+        .bind(game.cols)
+        .bind(difficulty(game.start_distance))
+        .bind(game.start_distance)
+        .bind(game.solved_moves.unwrap_or(game.moves))
+        .bind(game.elapsed_ms())
+        .bind(&game.daily)
+        .bind(&game.metric)
+        .bind(&game.start_board)
+        .bind(&game.history)
+        .execute(&self.0)
+        .await
+        .unwrap();
+    }
+
+    async fn bests(&self, session: &Session) -> Vec<Best> {
+        sqlx::query_as::<_, Best>(
+            "SELECT rows, cols, difficulty, metric, MIN(moves) AS moves,
+                    MIN(elapsed_ms) AS elapsed_ms, COUNT(*) AS solved
+             FROM results WHERE session = ?
+             GROUP BY rows, cols, difficulty, metric
+             ORDER BY rows * cols, rows,
+                      CASE difficulty WHEN 'easy' THEN 0 WHEN 'medium' THEN 1 ELSE 2 END, metric",
+        )
+        .bind(&session.0)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn best_before(&self, session: &Session, game: &Game) -> Option<Best> {
+        sqlx::query_as::<_, Best>(
+            "SELECT rows, cols, difficulty, metric, MIN(moves) AS moves,
+                    MIN(elapsed_ms) AS elapsed_ms, COUNT(*) AS solved
+             FROM results
+             WHERE session = ? AND rows = ? AND cols = ? AND difficulty = ? AND metric = ?
+                   AND game_id != ?
+             GROUP BY rows, cols, difficulty, metric",
+        )
+        .bind(&session.0)
+        .bind(game.rows)
+        .bind(game.cols)
+        .bind(difficulty(game.start_distance))
+        .bind(&game.metric)
+        .bind(game.id)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .pop()
+    }
+
+    async fn daily_result(&self, session: &Session, date: &str) -> Option<Solved> {
+        sqlx::query_as::<_, Solved>(
+            "SELECT moves, optimal, elapsed_ms FROM results WHERE session = ? AND daily = ?
+             ORDER BY id LIMIT 1",
+        )
+        .bind(&session.0)
+        .bind(date)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .pop()
+    }
+
+    async fn scores(&self, filter: &Filter, limit: usize) -> Vec<Entry> {
+        let since = match filter.period {
+            Period::Daily => "date(created_at) = date('now')",
+            Period::Weekly => "created_at >= datetime('now', '-7 days')",
+            Period::AllTime => "TRUE",
+        };
+        let order = match filter.metric {
+            Metric::Time => "elapsed_ms, moves",
+            Metric::Moves => "moves, elapsed_ms",
+            Metric::Efficiency => "efficiency DESC, elapsed_ms",
+        };
+
+        sqlx::query_as::<_, Entry>(&format!(
+            "SELECT name, moves, optimal, elapsed_ms, efficiency, created_at FROM scores
+             WHERE rows = ? AND cols = ? AND difficulty = ? AND metric = ? AND {}
+             ORDER BY {}, id LIMIT ?",
+            since, order
+        ))
+        .bind(filter.rows)
+        .bind(filter.cols)
+        .bind(&filter.difficulty)
+        .bind(&filter.move_metric)
+        .bind(limit as i64)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn daily_scores(&self, date: &str, limit: usize) -> Vec<Entry> {
+        sqlx::query_as::<_, Entry>(
+            "SELECT name, moves, optimal, elapsed_ms, efficiency, created_at FROM scores
+             WHERE daily = ? ORDER BY moves, elapsed_ms, id LIMIT ?",
+        )
+        .bind(date)
+        .bind(limit as i64)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+    }
+
+    // the unique indexes on game_id and (daily, session) turn a second go away
+    async fn add_score(&self, score: &Score) -> bool {
+        sqlx::query(
+            "INSERT INTO scores (session, game_id, name, rows, cols, difficulty, optimal, moves,
+                                 elapsed_ms, efficiency, start_board, history, daily, metric)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&score.session)
+        .bind(score.game_id)
+        .bind(&score.name)
+        .bind(score.rows)
+        .bind(score.cols)
+        .bind(&score.difficulty)
+        .bind(score.optimal)
+        .bind(score.moves)
+        .bind(score.elapsed_ms)
+        .bind(score.efficiency)
+        .bind(&score.start_board)
+        .bind(&score.history)
+        .bind(&score.daily)
+        .bind(&score.metric)
+        .execute(&self.0)
+        .await
+        .is_ok()
+    }
+
+    async fn add_picture(&self, session: &Session, token: &str) {
+        sqlx::query("INSERT INTO pictures (session, token) VALUES (?, ?)")
+            .bind(&session.0)
+            .bind(token)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn clear_pictures(&self, session: &Session) {
+        sqlx::query("UPDATE pictures SET active = FALSE WHERE session = ?")
+            .bind(&session.0)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn active_picture(&self, session: &Session) -> Option<String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT token FROM pictures WHERE session = ? AND active ORDER BY id DESC LIMIT 1",
+        )
+        .bind(&session.0)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .pop()
+    }
+
+    async fn add_race(&self, code: &str, seed: i64, board: &Board, distance: i32) -> bool {
+        sqlx::query("INSERT INTO races (code, seed, start_board, distance) VALUES (?, ?, ?, ?)")
+            .bind(code)
+            .bind(seed)
+            .bind(format_board(board))
+            .bind(distance)
+            .execute(&self.0)
+            .await
+            .is_ok()
+    }
+
+    async fn race(&self, code: &str) -> Option<Race> {
+        sqlx::query_as::<_, Race>(
+            "SELECT id, code, start_board, distance, winner FROM races WHERE code = ?",
+        )
+        .bind(code)
+        .fetch_optional(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn race_players(&self, race_id: i64) -> Vec<RacePlayer> {
+        sqlx::query_as::<_, RacePlayer>(
+            "SELECT seat, board, moves, finished_at IS NOT NULL AS finished
+             FROM race_players WHERE race_id = ? ORDER BY seat",
+        )
+        .bind(race_id)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn seat(&self, race_id: i64, session: &Session) -> Option<i32> {
+        sqlx::query_scalar::<_, i32>(
+            "SELECT seat FROM race_players WHERE race_id = ? AND session = ?",
+        )
+        .bind(race_id)
+        .bind(&session.0)
+        .fetch_optional(&self.0)
+        .await
+        .unwrap()
+    }
+
+    // the primary key and the unique session let only one of two at once through
+    async fn add_player(&self, race_id: i64, seat: i32, session: &Session, board: &Board) -> bool {
+        sqlx::query("INSERT INTO race_players (race_id, seat, session, board) VALUES (?, ?, ?, ?)")
+            .bind(race_id)
+            .bind(seat)
+            .bind(&session.0)
+            .bind(format_board(board))
+            .execute(&self.0)
+            .await
+            .is_ok()
+    }
+
+    async fn update_player(&self, race_id: i64, seat: i32, board: &Board, finished: bool) {
+        sqlx::query(
+            "UPDATE race_players
+             SET board = ?, moves = moves + 1,
+                 finished_at = CASE WHEN ? THEN CURRENT_TIMESTAMP END
+             WHERE race_id = ? AND seat = ?",
+        )
+        .bind(format_board(board))
+        .bind(finished)
+        .bind(race_id)
+        .bind(seat)
+        .execute(&self.0)
+        .await
+        .unwrap();
+    }
+
+    async fn set_winner(&self, race_id: i64, seat: i32) {
+        sqlx::query(
+            "UPDATE races SET winner = ?, finished_at = CURRENT_TIMESTAMP
+             WHERE id = ? AND winner IS NULL",
+        )
+        .bind(seat)
+        .bind(race_id)
+        .execute(&self.0)
+        .await
+        .unwrap();
+    }
+
+    async fn add_job(
+        &self,
+        session: &Session,
+        board: &Board,
+        metric: MoveMetric,
+        budget: usize,
+    ) -> i64 {
+        sqlx::query("INSERT INTO jobs (session, board, metric, budget) VALUES (?, ?, ?, ?)")
+            .bind(&session.0)
+            .bind(board.code())
+            .bind(metric.as_str())
+            .bind(budget as i64)
+            .execute(&self.0)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    async fn job(&self, id: i64) -> Option<Job> {
+        sqlx::query_as::<_, Job>(
+            "SELECT id, session, board, metric, budget, status, nodes, path, created_at,
+                    finished_at
+             FROM jobs WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn claim_job(&self) -> Option<Job> {
+        // one statement, so two workers can't both get the same one
+        let id = sqlx::query_scalar::<_, i64>(
+            "UPDATE jobs SET status = 'running', started_at = CURRENT_TIMESTAMP
+             WHERE id = (SELECT id FROM jobs WHERE status = 'queued' ORDER BY id LIMIT 1)
+             RETURNING id",
+        )
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+        .pop()?;
+        self.job(id).await
+    }
+
+    async fn job_progress(&self, id: i64, nodes: usize) {
+        sqlx::query("UPDATE jobs SET nodes = ? WHERE id = ?")
+            .bind(nodes as i64)
+            .bind(id)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn finish_job(&self, id: i64, nodes: usize, path: Option<&[Board]>) {
+        let status = if path.is_some() { DONE } else { GAVE_UP };
+        let path = path.map(|steps| {
+            steps
+                .iter()
+                .map(|step| step.code())
+                .collect::<Vec<String>>()
+                .join(" ")
+        });
+        sqlx::query(
+            "UPDATE jobs SET status = ?, nodes = ?, path = ?, finished_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+        )
+        .bind(status)
+        .bind(nodes as i64)
+        .bind(path)
+        .bind(id)
+        .execute(&self.0)
+        .await
+        .unwrap();
+    }
+
+    async fn requeue_jobs(&self) -> usize {
+        sqlx::query("UPDATE jobs SET status = 'queued', started_at = NULL WHERE status = 'running'")
+            .execute(&self.0)
+            .await
+            .unwrap()
+            .rows_affected() as usize
+    }
+
+    async fn add_user(&self, name: &str, password_hash: &str) -> Option<i64> {
+        sqlx::query("INSERT INTO users (name, password_hash) VALUES (?, ?)")
+            .bind(name)
+            .bind(password_hash)
+            .execute(&self.0)
+            .await
+            .ok()
+            .map(|done| done.last_insert_rowid())
+    }
+
+    async fn user(&self, name: &str) -> Option<User> {
+        sqlx::query_as::<_, User>(
+            "SELECT id, name, password_hash, created_at FROM users WHERE name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn add_login(&self, token_hash: &str, user_id: i64, expires_ms: i64) {
+        sqlx::query("DELETE FROM logins WHERE expires_ms <= ?")
+            .bind(now_ms())
+            .execute(&self.0)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO logins (token, user_id, expires_ms) VALUES (?, ?, ?)")
+            .bind(token_hash)
+            .bind(user_id)
+            .bind(expires_ms)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn login(&self, token_hash: &str) -> Option<User> {
+        sqlx::query_as::<_, User>(
+            "SELECT users.id, users.name, users.password_hash, users.created_at
+             FROM logins JOIN users ON users.id = logins.user_id
+             WHERE logins.token = ? AND logins.expires_ms > ?",
+        )
+        .bind(token_hash)
+        .bind(now_ms())
+        .fetch_optional(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn remove_login(&self, token_hash: &str) {
+        sqlx::query("DELETE FROM logins WHERE token = ?")
+            .bind(token_hash)
+            .execute(&self.0)
+            .await
+            .unwrap();
+    }
+
+    async fn merge_sessions(&self, from: &Session, into: &Session) {
+        let mut tx = self.0.begin().await.unwrap();
+        // the game in front of the player is the one they keep playing. looked
+        // at in here so a game from starts meanwhile can't slip in between
+        let playing = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM full_path WHERE iteration_index = 0 AND session = ?)",
+        )
+        .bind(&from.0)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        if playing {
+            for clear in [
+                "DELETE FROM full_path WHERE session = ?",
+                "DELETE FROM solution_steps
+                 WHERE solution_id IN (SELECT id FROM solutions WHERE session = ?)",
+                "DELETE FROM solutions WHERE session = ?",
+                "DELETE FROM games WHERE session = ?",
+            ] {
+                sqlx::query(clear)
+                    .bind(&into.0)
+                    .execute(&mut *tx)
+                    .await
+                    .unwrap();
+            }
+        }
+        // a picture already up on the account stays the one shown
+        sqlx::query(
+            "UPDATE pictures SET session = ?1,
+                 active = active AND NOT EXISTS
+                     (SELECT 1 FROM pictures WHERE session = ?1 AND active)
+             WHERE session = ?2",
+        )
+        .bind(&into.0)
+        .bind(&from.0)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+        // OR IGNORE for the one score a day and one seat a race, the account's
+        // own stay and the guest's are left behind
+        for table in [
+            "UPDATE full_path SET session = ?1 WHERE session = ?2",
+            "UPDATE games SET session = ?1 WHERE session = ?2",
+            "UPDATE solutions SET session = ?1 WHERE session = ?2",
+            "UPDATE results SET session = ?1 WHERE session = ?2",
+            "UPDATE OR IGNORE scores SET session = ?1 WHERE session = ?2",
+            "UPDATE OR IGNORE daily_starts SET session = ?1 WHERE session = ?2",
+            "UPDATE OR IGNORE race_players SET session = ?1 WHERE session = ?2",
+            "UPDATE jobs SET session = ?1 WHERE session = ?2",
+        ] {
+            sqlx::query(table)
+                .bind(&into.0)
+                .bind(&from.0)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+
+        tx.commit().await.unwrap();
+    }
+
+    async fn past_games(&self, session: &Session, limit: usize) -> Vec<PastGame> {
+        sqlx::query_as::<_, PastGame>(
+            "SELECT id, rows, cols, difficulty, metric, optimal, moves, elapsed_ms, daily,
+                    solved_at, start_board, history
+             FROM results WHERE session = ? ORDER BY id DESC LIMIT ?",
+        )
+        .bind(&session.0)
+        .bind(limit as i64)
+        .fetch_all(&self.0)
+        .await
+        .unwrap()
+    }
+
+    async fn past_game(&self, session: &Session, id: i64) -> Option<PastGame> {
+        sqlx::query_as::<_, PastGame>(
+            "SELECT id, rows, cols, difficulty, metric, optimal, moves, elapsed_ms, daily,
+                    solved_at, start_board, history
+             FROM results WHERE session = ? AND id = ?",
+        )
+        .bind(&session.0)
+        .bind(id)
+        .fetch_optional(&self.0)
+        .await
+        .unwrap()
+    }
+}
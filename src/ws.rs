@@ -0,0 +1,225 @@
+use askama::Template;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, State};
+use axum::response::IntoResponse;
+use axum::Extension;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::SolutionCache;
+use crate::limit::Limits;
+use crate::session::Session;
+use crate::slayin::Board;
+use crate::store::Store;
+use crate::{apply_move, current_board, current_game, find_game, start_game, Game, MoveFeedback};
+
+// what the browser sends over /ws
+//   {"type": "new_game"} | {"type": "move", "idx": 5} | {"type": "hint"} | {"type": "solve"}
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    NewGame,
+    Move { idx: usize },
+    Hint,
+    Solve,
+}
+
+// what goes back, a board right after connecting and after every move
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Board {
+        cells: Vec<i32>,
+        cols: usize,
+        moves: i32,
+        distance: i32,
+        start_distance: i32,
+        elapsed_ms: i64,
+        feedback: Option<MoveFeedback>,
+    },
+    Hint {
+        idx: Option<usize>,
+        distance: usize,
+    },
+    Solving,
+    // boards the solver's looked at so far, every so often while it's solving
+    Progress {
+        nodes: usize,
+    },
+    Solution {
+        steps: Vec<Vec<i32>>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    fn board(board: Board, game: Game, feedback: Option<MoveFeedback>) -> ServerMessage {
+        ServerMessage::Board {
+            cells: board.cells,
+            cols: board.cols,
+            moves: game.moves,
+            distance: game.distance,
+            start_distance: game.start_distance,
+            elapsed_ms: game.elapsed_ms(),
+            feedback,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "play.html")]
+struct PlayTemplate {
+    title: String,
+}
+
+pub async fn play() -> impl IntoResponse {
+    PlayTemplate {
+        title: String::from("play"),
+    }
+}
+
+pub async fn handler(
+    ws: WebSocketUpgrade,
+    State(store): State<Store>,
+    State(solutions): State<SolutionCache>,
+    State(limits): State<Limits>,
+    connected: Option<ConnectInfo<SocketAddr>>,
+    Extension(session): Extension<Session>,
+) -> impl IntoResponse {
+    let ip = connected.map(|ConnectInfo(addr)| addr.ip());
+    ws.on_upgrade(move |socket| play_session(socket, store, solutions, limits, ip, session))
+}
+
+async fn play_session(
+    mut socket: WebSocket,
+    store: Store,
+    solutions: SolutionCache,
+    limits: Limits,
+    ip: Option<IpAddr>,
+    session: Session,
+) {
+    // pick up the game the http routes are on, or start one for a new player
+    let board = match find_game(&store, &session).await {
+        Some(game) => ServerMessage::board(current_board(&store, &session).await, game, None),
+        None => new_game(&store, &session).await,
+    };
+    if send(&mut socket, &board).await.is_err() {
+        return;
+    }
+
+    while let Some(Ok(message)) = socket.recv().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        // every message runs the solver, so each one's limited like a request
+        // to the solving routes, the slot held until it's answered
+        let result = match serde_json::from_str::<ClientMessage>(&text) {
+            Ok(message) => match limits.check(ip, Some(&session.0)) {
+                Ok(_solving) => handle(&mut socket, &store, &solutions, &session, message).await,
+                Err(limited) => {
+                    let message = ServerMessage::Error {
+                        message: limited.message,
+                    };
+                    send(&mut socket, &message).await
+                }
+            },
+            Err(error) => {
+                let message = ServerMessage::Error {
+                    message: error.to_string(),
+                };
+                send(&mut socket, &message).await
+            }
+        };
+        if result.is_err() {
+            break;
+        }
+    }
+}
+
+async fn handle(
+    socket: &mut WebSocket,
+    store: &Store,
+    solutions: &SolutionCache,
+    session: &Session,
+    message: ClientMessage,
+) -> Result<(), axum::Error> {
+    match message {
+        ClientMessage::NewGame => send(socket, &new_game(store, session).await).await,
+        ClientMessage::Move { idx } => match apply_move(store, solutions, session, idx).await {
+            Some((board, game, feedback)) => {
+                send(socket, &ServerMessage::board(board, game, feedback)).await
+            }
+            None => {
+                let message = ServerMessage::Error {
+                    message: format!("tile {} isn't in line with the blank", idx),
+                };
+                send(socket, &message).await
+            }
+        },
+        ClientMessage::Hint => {
+            let board = current_board(store, session).await;
+            let metric = current_game(store, session).await.move_metric();
+            let message = match solutions.solve(&board, metric).await {
+                Some(steps) => ServerMessage::Hint {
+                    idx: steps.get(1).map(|next| Board::find_zero(&next.cells)),
+                    distance: steps.len() - 1,
+                },
+                None => ServerMessage::Error {
+                    message: String::from("the board is too far from solved to work out"),
+                },
+            };
+            send(socket, &message).await
+        }
+        ClientMessage::Solve => {
+            send(socket, &ServerMessage::Solving).await?;
+
+            let board = current_board(store, session).await;
+            let metric = current_game(store, session).await.move_metric();
+            let (progress, mut looked_at) = tokio::sync::mpsc::unbounded_channel();
+            let solving = solutions.solve_reporting(&board, metric, move |nodes| {
+                let _ = progress.send(nodes);
+            });
+            tokio::pin!(solving);
+            let steps = loop {
+                tokio::select! {
+                    steps = &mut solving => break steps,
+                    Some(nodes) = looked_at.recv() => {
+                        send(socket, &ServerMessage::Progress { nodes }).await?;
+                    }
+                }
+            };
+            // the total the solver ended on, it can beat the solve back here
+            while let Ok(nodes) = looked_at.try_recv() {
+                send(socket, &ServerMessage::Progress { nodes }).await?;
+            }
+
+            let message = match steps {
+                Some(steps) => ServerMessage::Solution {
+                    steps: steps.into_iter().map(|board| board.cells).collect(),
+                },
+                None => ServerMessage::Error {
+                    message: String::from("the board is too far from solved to work out"),
+                },
+            };
+            send(socket, &message).await
+        }
+    }
+}
+
+// a random 3x3 is always within the solver's budget
+async fn new_game(store: &Store, session: &Session) -> ServerMessage {
+    let board = Board::random_board();
+    let game = start_game(store, session, &board).await.unwrap();
+    ServerMessage::board(board, game, None)
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap();
+    socket.send(Message::Text(text)).await
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::{send, spawn_app, spawn_database, Db, FORM};
+use serde_json::Value;
+
+// the account cookie's value out of a login's response
+fn account_cookie(head: &str) -> String {
+    let set = head
+        .lines()
+        .find(|line| line.to_lowercase().starts_with("set-cookie: account="))
+        .unwrap();
+    let cookie = set.split_once(": ").unwrap().1;
+    cookie.split(';').next().unwrap().to_owned()
+}
+
+// a 2x2 played and solved the long way round, as guest
+async fn solve_one(addr: std::net::SocketAddr, guest: &str) {
+    let cookies = format!("session={}", guest);
+    let form = "rows=2&cols=2&cell=1&cell=2&cell=3&cell=";
+    send(addr, "POST", "/edit/play", &cookies, FORM, form).await;
+    send(addr, "GET", "/slide?idx=2", &cookies, FORM, "").await;
+    send(addr, "GET", "/slide?idx=3", &cookies, FORM, "").await;
+}
+
+#[tokio::test]
+async fn signs_up_logs_in_and_out() {
+    let addr = spawn_app("in-and-out").await;
+    let form = "name=ada&password=correct+horse&keep=on";
+    let (status, head, _) = send(addr, "POST", "/register", "session=ada", FORM, form).await;
+    assert_eq!(status, 303);
+    assert!(head.contains("location: /u/ada"), "{}", head);
+    let set = head.lines().find(|line| line.contains("account=")).unwrap();
+    assert!(
+        set.contains("HttpOnly") && set.contains("SameSite=Lax"),
+        "{}",
+        set
+    );
+    let account = account_cookie(&head);
+
+    let (status, head, _) = send(addr, "GET", "/profile", &account, FORM, "").await;
+    assert_eq!(status, 303);
+    assert!(head.contains("location: /u/ada"));
+    let (_, _, body) = send(addr, "GET", "/u/ada", &account, FORM, "").await;
+    assert!(body.contains("LOG OUT"));
+    // anyone can look, only ada gets to log out
+    let (status, _, body) = send(addr, "GET", "/u/ADA", "session=bob", FORM, "").await;
+    assert_eq!(status, 200);
+    assert!(body.contains("ada") && !body.contains("LOG OUT"));
+
+    let (status, head, _) = send(addr, "POST", "/logout", &account, FORM, "").await;
+    assert_eq!(status, 303);
+    assert!(head.contains("account=;"), "{}", head);
+    // the token's no good once it's logged out, even kept around
+    let (_, head, _) = send(addr, "GET", "/profile", &account, FORM, "").await;
+    assert!(head.contains("location: /login"));
+
+    let form = "name=Ada&password=correct+horse";
+    let (status, head, _) = send(addr, "POST", "/login", "session=ada", FORM, form).await;
+    assert_eq!(status, 303);
+    let account = account_cookie(&head);
+    let (_, head, _) = send(addr, "GET", "/profile", &account, FORM, "").await;
+    assert!(head.contains("location: /u/ada"));
+}
+
+#[tokio::test]
+async fn refuses_bad_names_and_passwords() {
+    let addr = spawn_app("refuses").await;
+    let cookies = "session=carol";
+    let form = "name=c&password=correct+horse";
+    let (status, _, body) = send(addr, "POST", "/register", cookies, FORM, form).await;
+    assert_eq!(status, 422);
+    assert!(body.contains("3 to 20 characters"));
+    let form = "name=carol&password=short";
+    let (status, _, body) = send(addr, "POST", "/register", cookies, FORM, form).await;
+    assert_eq!(status, 422);
+    assert!(body.contains("8 characters at least"));
+
+    let form = "name=carol&password=correct+horse";
+    assert_eq!(
+        send(addr, "POST", "/register", cookies, FORM, form).await.0,
+        303
+    );
+    let form = "name=CAROL&password=another+one";
+    let (status, _, body) = send(addr, "POST", "/register", cookies, FORM, form).await;
+    assert_eq!(status, 422);
+    assert!(body.contains("CAROL is taken"));
+
+    let form = "name=carol&password=correct+horsE";
+    let (status, head, body) = send(addr, "POST", "/login", cookies, FORM, form).await;
+    assert_eq!(status, 422);
+    assert!(body.contains("don&#x27;t go together"), "{}", body);
+    assert!(!head.contains("account="));
+    let form = "name=nobody&password=correct+horse";
+    let (status, _, body) = send(addr, "POST", "/login", cookies, FORM, form).await;
+    assert_eq!(status, 422);
+    assert!(body.contains("don&#x27;t go together"));
+}
+
+#[tokio::test]
+async fn keeps_what_a_guest_played_when_asked() {
+    let addr = spawn_app("keeps").await;
+    solve_one(addr, "dave-guest").await;
+    let form = "name=dave&password=correct+horse&keep=on";
+    let (_, head, _) = send(addr, "POST", "/register", "session=dave-guest", FORM, form).await;
+    let account = account_cookie(&head);
+
+    let (_, _, body) = send(addr, "GET", "/u/dave", &account, FORM, "").await;
+    assert!(body.contains("1 solved"), "{}", body);
+    assert!(body.contains("2x2"));
+    // the game in progress came along too, so it goes on the leaderboard under
+    // the account's name whatever gets typed
+    let score = r#"{"name": "someone else", "history": [2, 3]}"#;
+    let json = "application/json";
+    let (status, _, _) = send(addr, "POST", "/api/leaderboard", &account, json, score).await;
+    assert_eq!(status, 201);
+    let path = "/api/leaderboard?rows=2&cols=2&difficulty=easy";
+    let (_, _, body) = send(addr, "GET", path, "session=anyone", json, "").await;
+    let entries: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(entries[0]["name"], "dave");
+
+    // left unticked, the guest's games stay the guest's
+    solve_one(addr, "erin-guest").await;
+    let form = "name=erin&password=correct+horse";
+    let (_, head, _) = send(addr, "POST", "/register", "session=erin-guest", FORM, form).await;
+    let account = account_cookie(&head);
+    let (_, _, body) = send(addr, "GET", "/u/erin", &account, FORM, "").await;
+    assert!(body.contains("0 solved"));
+    let (_, _, body) = send(addr, "GET", "/bests", "session=erin-guest", FORM, "").await;
+    assert!(body.contains("2x2"));
+}
+
+#[tokio::test]
+async fn an_accounts_session_only_comes_from_logging_in() {
+    let addr = spawn_app("made-up").await;
+    solve_one(addr, "frank-guest").await;
+    let form = "name=frank&password=correct+horse&keep=on";
+    let (_, head, _) = send(addr, "POST", "/register", "session=frank-guest", FORM, form).await;
+    let account = account_cookie(&head);
+    let (_, _, body) = send(addr, "GET", "/bests", &account, FORM, "").await;
+    assert!(body.contains("2x2"));
+
+    let (_, head, body) = send(addr, "GET", "/bests", "session=user-1", FORM, "").await;
+    assert!(body.contains("nothing solved yet"));
+    assert!(head.contains("set-cookie: session="), "{}", head);
+}
+
+#[tokio::test]
+async fn logins_keep_a_hash_and_run_out() {
+    let db = Db::new("expiry");
+    let (addr, pool) = spawn_database(&db).await;
+    let form = "name=gus&password=correct+horse";
+    let (_, head, _) = send(addr, "POST", "/register", "session=gus", FORM, form).await;
+    let account = account_cookie(&head);
+
+    let token = account.trim_start_matches("account=");
+    let (kept,): (String,) = sqlx::query_as("SELECT token FROM logins")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_ne!(kept, token);
+    assert_eq!(kept.len(), 64);
+    let (_, head, _) = send(addr, "GET", "/profile", &account, FORM, "").await;
+    assert!(head.contains("location: /u/gus"));
+
+    sqlx::query("UPDATE logins SET expires_ms = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, head, _) = send(addr, "GET", "/profile", &account, FORM, "").await;
+    assert!(head.contains("location: /login"), "{}", head);
+    assert!(head.contains("account=;"), "{}", head);
+}
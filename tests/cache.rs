@@ -0,0 +1,82 @@
+mod common;
+
+use common::{spawn_config, Db, FORM, START};
+use serde_json::Value;
+use slidin_puzzle::config::Config;
+
+// the same database can be served again to see what outlives a restart
+async fn spawn_app(db: &Db, cache_size: usize, cache_keep: usize) -> std::net::SocketAddr {
+    let config = Config {
+        database_url: db.url(),
+        cache_size,
+        cache_keep,
+        ..Config::default()
+    };
+    spawn_config(&config).await
+}
+
+// just the body
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    body: &str,
+) -> String {
+    let cookies = format!("session={}", session);
+    common::send(addr, method, path, &cookies, FORM, body)
+        .await
+        .2
+}
+
+async fn stats(addr: std::net::SocketAddr) -> Value {
+    serde_json::from_str(&request(addr, "GET", "/api/solver/cache", "anyone", "").await).unwrap()
+}
+
+// 1 2 3 / 4 5 6 / 7 _ 8, one
+const CLOSER: &str = "rows=3&cols=3&cell=1&cell=2&cell=3&cell=4&cell=5&cell=6&cell=7&cell=&cell=8";
+
+#[tokio::test]
+async fn the_same_board_is_solved_once() {
+    let db = Db::new("shared");
+    let addr = spawn_app(&db, 16, 16).await;
+
+    for session in ["ada", "bob"] {
+        request(addr, "POST", "/edit/play", session, START).await;
+        let solving = request(addr, "GET", "/rest_iterations", session, "").await;
+        assert!(solving.contains("1 / 3"), "{}", solving);
+    }
+    let counted = stats(addr).await;
+    assert_eq!(counted["misses"], 1);
+    assert_eq!(counted["memory_hits"], 1);
+
+    // a restart starts with nothing in memory, the database still has it
+    let addr = spawn_app(&db, 16, 16).await;
+    request(addr, "POST", "/edit/play", "cy", START).await;
+    let solving = request(addr, "GET", "/rest_iterations", "cy", "").await;
+    assert!(solving.contains("1 / 3"), "{}", solving);
+    let counted = stats(addr).await;
+    assert_eq!(counted["misses"], 0);
+    assert_eq!(counted["store_hits"], 1);
+}
+
+#[tokio::test]
+async fn the_least_recently_used_go_first() {
+    let db = Db::new("evict");
+    let addr = spawn_app(&db, 1, 1).await;
+
+    request(addr, "POST", "/edit/play", "ada", START).await;
+    request(addr, "GET", "/rest_iterations", "ada", "").await;
+    request(addr, "POST", "/edit/play", "ada", CLOSER).await;
+    request(addr, "GET", "/rest_iterations", "ada", "").await;
+    let counted = stats(addr).await;
+    assert_eq!(counted["entries"], 1);
+    assert_eq!(counted["memory_evictions"], 1);
+    assert_eq!(counted["store_evictions"], 1);
+
+    // pushed out of both, so it gets solved again
+    request(addr, "POST", "/edit/play", "ada", START).await;
+    let solving = request(addr, "GET", "/rest_iterations", "ada", "").await;
+    assert!(solving.contains("1 / 3"), "{}", solving);
+    assert_eq!(stats(addr).await["misses"], 3);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{spawn_app, FORM};
+use rand::seq::SliceRandom;
+use slidin_puzzle::slayin::Board;
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    common::request(addr, "GET", path, "", FORM, "").await
+}
+
+fn round_trips(board: &Board) {
+    let code = board.code();
+    let back = Board::from_code(&code).unwrap();
+    assert_eq!(back.cells, board.cells, "{}", code);
+    assert_eq!((back.rows, back.cols), (board.rows, board.cols), "{}", code);
+    assert_eq!(back.code(), code);
+}
+
+#[test]
+fn every_small_board_round_trips() {
+    let mut cells: Vec<i32> = (0..6).collect();
+    // all 720 orderings of a 2x3, by swapping through heap's algorithm
+    let mut counters = [0; 6];
+    round_trips(&Board::with_size(cells.clone(), 2, 3));
+    let mut i = 0;
+    while i < 6 {
+        if counters[i] < i {
+            cells.swap(if i % 2 == 0 { 0 } else { counters[i] }, i);
+            round_trips(&Board::with_size(cells.clone(), 2, 3));
+            round_trips(&Board::with_size(cells.clone(), 3, 2));
+            counters[i] += 1;
+            i = 0;
+        } else {
+            counters[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+#[test]
+fn big_boards_round_trip() {
+    let mut rng = rand::thread_rng();
+    for (rows, cols) in [(4, 4), (5, 5), (4, 5), (5, 2)] {
+        for _ in 0..100 {
+            let mut cells: Vec<i32> = (0..(rows * cols) as i32).collect();
+            cells.shuffle(&mut rng);
+            round_trips(&Board::with_size(cells, rows, cols));
+        }
+        // first and last rank
+        let goal = Board::goal(rows, cols);
+        round_trips(&Board::with_size(goal.clone(), rows, cols));
+        round_trips(&Board::with_size(
+            goal.into_iter().rev().collect(),
+            rows,
+            cols,
+        ));
+    }
+}
+
+#[test]
+fn rejects_codes_that_arent_boards() {
+    assert!(Board::from_code("").is_err());
+    assert!(Board::from_code("5").is_err());
+    // shape digit past 5x5
+    assert!(Board::from_code("Z1").is_err());
+    assert!(Board::from_code("5a-b").is_err());
+    // leading zeros would give two codes for one board
+    assert!(Board::from_code("500").is_err());
+    // a 3x3 only has 9! orderings
+    assert!(Board::from_code("5zzzz").is_err());
+}
+
+#[tokio::test]
+async fn permalinks_load_into_the_slide_view() {
+    let addr = spawn_app("permalink").await;
+    let board = Board::with_size(vec![1, 2, 3, 4, 5, 6, 7, 0, 8], 3, 3);
+    let page = get(addr, &format!("/p/{}", board.code())).await;
+    assert!(page.starts_with("HTTP/1.1 200"));
+    assert!(page.contains("a shared board"));
+    assert!(page.contains(&format!("/p/{}", board.code())));
+
+    let unsolvable = Board::with_size(vec![2, 1, 3, 4, 5, 6, 7, 8, 0], 3, 3);
+    let page = get(addr, &format!("/p/{}", unsolvable.code())).await;
+    assert!(page.starts_with("HTTP/1.1 400"));
+    assert!(page.contains("can never be solved"));
+
+    let page = get(addr, "/p/nope!").await;
+    assert!(page.starts_with("HTTP/1.1 400"));
+    assert!(page.contains("isn&#x27;t a board code"));
+}
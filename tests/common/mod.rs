@@ -0,0 +1,177 @@
+// what the integration tests share: servers on random ports, a raw http client
+// and the websocket plumbing. every test binary uses its own part of it
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use slidin_puzzle::config::Config;
+use sqlx::SqlitePool;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+pub const FORM: &str = "application/x-www-form-urlencoded";
+pub const JSON: &str = "application/json";
+
+// 1 2 3 / 4 5 6 / _ 7 8, two slides from solved
+pub const START: &str =
+    "rows=3&cols=3&cell=1&cell=2&cell=3&cell=4&cell=5&cell=6&cell=&cell=7&cell=8";
+
+pub type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// a database file of the test's own, gone again once whatever holds it is
+// dropped, sqlite's -wal and -shm along with it. names only need to differ
+// within a test binary, the process id keeps the binaries apart
+pub struct Db(PathBuf);
+
+impl Db {
+    pub fn new(name: &str) -> Db {
+        let path =
+            std::env::temp_dir().join(format!("slidin-puzzle-{}-{}.db", name, std::process::id()));
+        let db = Db(path);
+        // left over from a run that didn't get to clean up
+        db.remove();
+        db
+    }
+
+    pub fn url(&self) -> String {
+        format!("sqlite://{}", self.0.display())
+    }
+
+    fn remove(&self) {
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let _ = std::fs::remove_file(format!("{}{}", self.0.display(), suffix));
+        }
+    }
+}
+
+impl Drop for Db {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+// serves app until the test's runtime shuts down, which drops it and whatever
+// it's been given to keep
+async fn serve(app: axum::Router, keep: Option<Db>) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let _keep = keep;
+        axum::serve(listener, app).await.unwrap();
+    });
+    addr
+}
+
+// a server over a fresh database, deleted when the test ends
+pub async fn spawn_app(name: &str) -> SocketAddr {
+    let db = Db::new(name);
+    let pool = slidin_puzzle::initialize_database(&db.url()).await;
+    serve(slidin_puzzle::app(pool), Some(db)).await
+}
+
+// a server over db, with the pool to look under it. the test keeps db, so the
+// same database can be served again to see what outlives a restart
+pub async fn spawn_database(db: &Db) -> (SocketAddr, SqlitePool) {
+    let pool = slidin_puzzle::initialize_database(&db.url()).await;
+    let addr = serve(slidin_puzzle::app(pool.clone()), None).await;
+    (addr, pool)
+}
+
+// a server built from config the way main builds it
+pub async fn spawn_config(config: &Config) -> SocketAddr {
+    serve(slidin_puzzle::build(config).await, None).await
+}
+
+// one request on a connection of its own, the whole response back as text.
+// head is the request line and any headers past Host, Content-Length and
+// Connection
+pub async fn exchange(addr: SocketAddr, head: &str, body: &[u8]) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut request = format!(
+        "{}\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        head,
+        addr,
+        body.len()
+    )
+    .into_bytes();
+    request.extend_from_slice(body);
+    stream.write_all(&request).await.unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.unwrap();
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+// the whole response to a request sending cookies as they are, none when it's
+// empty
+pub async fn request(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    cookies: &str,
+    content_type: &str,
+    body: &str,
+) -> String {
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nContent-Type: {}",
+        method, path, content_type
+    );
+    if !cookies.is_empty() {
+        head.push_str(&format!("\r\nCookie: {}", cookies));
+    }
+    exchange(addr, &head, body.as_bytes()).await
+}
+
+// (status, head, body) of a response
+pub fn split(response: &str) -> (u16, String, String) {
+    let status = response[9..12].parse().unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, head.to_owned(), body.to_owned())
+}
+
+// request, split up
+pub async fn send(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    cookies: &str,
+    content_type: &str,
+    body: &str,
+) -> (u16, String, String) {
+    split(&request(addr, method, path, cookies, content_type, body).await)
+}
+
+// a websocket on path, sending cookies like request does
+pub async fn connect(addr: SocketAddr, path: &str, cookies: &str) -> Socket {
+    let mut request = format!("ws://{}{}", addr, path)
+        .into_client_request()
+        .unwrap();
+    if !cookies.is_empty() {
+        request
+            .headers_mut()
+            .insert("Cookie", cookies.parse().unwrap());
+    }
+    connect_async(request).await.unwrap().0
+}
+
+pub async fn send_json(socket: &mut Socket, message: Value) {
+    socket
+        .send(Message::Text(message.to_string()))
+        .await
+        .unwrap();
+}
+
+// the next json message, pings and the like skipped
+pub async fn recv(socket: &mut Socket) -> Value {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
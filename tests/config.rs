@@ -0,0 +1,97 @@
+use slidin_puzzle::config::{Config, LogFormat};
+
+// a toml file of its own per test, they run side by side. each test removes
+// its file once it's been read
+fn file(name: &str, toml: &str) -> String {
+    let path = std::env::temp_dir().join(format!(
+        "slidin-puzzle-config-{}-{}.toml",
+        name,
+        std::process::id()
+    ));
+    std::fs::write(&path, toml).unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn flags_beat_the_file_and_the_file_beats_the_defaults() {
+    let path = file(
+        "layers",
+        r#"
+            bind = "0.0.0.0:9000"
+            database_url = "sqlite://from-the-file.db"
+            log_format = "json"
+            secure_cookies = true
+
+            [solver]
+            node_budget = 1234
+            workers = 3
+
+            [limits]
+            max_tiles = 16
+        "#,
+    );
+    let config = Config::from_args([
+        "slidin-puzzle",
+        "--config",
+        &path,
+        "--bind",
+        "127.0.0.1:9001",
+        "--log-level",
+        "debug",
+    ])
+    .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(config.bind.to_string(), "127.0.0.1:9001");
+    assert_eq!(config.database_url, "sqlite://from-the-file.db");
+    assert_eq!(config.node_budget, 1234);
+    assert_eq!(config.workers, 3);
+    assert_eq!(config.max_tiles, 16);
+    assert!(config.secure_cookies);
+    assert_eq!(config.per_session, Config::default().per_session);
+    assert_eq!(config.log_level, "debug");
+    assert_eq!(config.log_format, LogFormat::Json);
+    assert_eq!(config.assets, Config::default().assets);
+}
+
+#[test]
+fn refuses_settings_that_make_no_sense() {
+    let error = Config::from_args(["slidin-puzzle", "--log-level", "loud"]).unwrap_err();
+    assert!(error.contains("isn't one of"));
+
+    let error = Config::from_args(["slidin-puzzle", "--log-format", "xml"]).unwrap_err();
+    assert!(error.contains("--log-format"));
+
+    let error = Config::from_args(["slidin-puzzle", "--node-budget", "0"]).unwrap_err();
+    assert!(error.contains("node budget"));
+
+    let error = Config::from_args(["slidin-puzzle", "--workers", "0"]).unwrap_err();
+    assert!(error.contains("at least one worker"));
+
+    let error = Config::from_args(["slidin-puzzle", "--max-solves", "0"]).unwrap_err();
+    assert!(error.contains("at least one request"));
+    let error = Config::from_args(["slidin-puzzle", "--max-tiles", "3"]).unwrap_err();
+    assert!(error.contains("smallest board"));
+
+    let error = Config::from_args(["slidin-puzzle", "--bind", "nowhere"]).unwrap_err();
+    assert!(error.contains("--bind"));
+
+    // a typo in the file shouldn't quietly fall back to the default
+    let path = file("typo", "[solver]\nnode_budjet = 10\n");
+    let error = Config::from_args(["slidin-puzzle", "--config", &path]).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("node_budjet"), "{}", error);
+
+    let error =
+        Config::from_args(["slidin-puzzle", "--config", "/no/such/slidin.toml"]).unwrap_err();
+    assert!(error.contains("can't read"));
+}
+
+#[test]
+fn help_shows_the_defaults_it_falls_back_to() {
+    let help = Config::from_args(["slidin-puzzle", "--help"]).unwrap_err();
+    let default = Config::default();
+    assert!(help.contains(&format!("[default: {}]", default.bind)));
+    assert!(help.contains(&format!("[default: {}]", default.max_tiles)));
+    assert!(help.contains(&format!("[default: {}]", default.job_budget)));
+}
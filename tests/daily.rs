@@ -0,0 +1,69 @@
+mod common;
+
+use common::{recv, send_json, spawn_app, Socket, FORM};
+use serde_json::json;
+
+async fn get(addr: std::net::SocketAddr, path: &str, session: &str) -> String {
+    let cookies = format!("session={}", session);
+    common::request(addr, "GET", path, &cookies, FORM, "").await
+}
+
+// the /ws socket picks up whatever game the session is on
+async fn connect(addr: std::net::SocketAddr, session: &str) -> Socket {
+    common::connect(addr, "/ws", &format!("session={}", session)).await
+}
+
+#[tokio::test]
+async fn everyone_gets_the_same_board() {
+    let addr = spawn_app("same").await;
+    get(addr, "/daily", "ada").await;
+    get(addr, "/daily", "bob").await;
+
+    let ada = recv(&mut connect(addr, "ada").await).await;
+    let bob = recv(&mut connect(addr, "bob").await).await;
+    assert_eq!(ada["cells"], bob["cells"]);
+    let distance = ada["start_distance"].as_i64().unwrap();
+    assert!((10..=19).contains(&distance));
+
+    // coming back to it doesn't deal a new one
+    get(addr, "/daily", "ada").await;
+    assert_eq!(
+        recv(&mut connect(addr, "ada").await).await["cells"],
+        ada["cells"]
+    );
+}
+
+#[tokio::test]
+async fn going_back_to_it_keeps_the_clock_running() {
+    let addr = spawn_app("clock").await;
+    get(addr, "/daily", "ada").await;
+
+    // off to another board for a bit, then back again
+    get(addr, "/first_iteration", "ada").await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    get(addr, "/daily", "ada").await;
+
+    let board = recv(&mut connect(addr, "ada").await).await;
+    assert!(board["elapsed_ms"].as_i64().unwrap() >= 300, "{}", board);
+}
+
+#[tokio::test]
+async fn solving_it_gives_a_summary_to_share() {
+    let addr = spawn_app("share").await;
+    get(addr, "/daily", "ada").await;
+
+    // follow the hints all the way home
+    let mut socket = connect(addr, "ada").await;
+    let mut board = recv(&mut socket).await;
+    let optimal = board["start_distance"].clone();
+    while board["distance"] != 0 {
+        send_json(&mut socket, json!({"type": "hint"})).await;
+        let hint = recv(&mut socket).await;
+        send_json(&mut socket, json!({"type": "move", "idx": hint["idx"]})).await;
+        board = recv(&mut socket).await;
+    }
+
+    let page = get(addr, "/daily", "ada").await;
+    assert!(page.contains(&format!("moves (optimal {})", optimal)));
+    assert!(page.contains(&format!("{} moves", optimal)));
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{recv, spawn_app, FORM};
+
+// sends the editor form as the given session and hands back the whole response
+async fn submit(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    form: &str,
+) -> String {
+    let cookies = format!("session={}", session);
+    common::request(addr, method, path, &cookies, FORM, form).await
+}
+
+fn cells(rows: usize, cols: usize, cells: &[&str]) -> String {
+    let mut form = format!("rows={}&cols={}", rows, cols);
+    for cell in cells {
+        form.push_str(&format!("&cell={}", cell));
+    }
+    form
+}
+
+#[tokio::test]
+async fn reports_duplicate_and_missing_tiles() {
+    let addr = spawn_app("duplicates").await;
+    let form = cells(3, 3, &["1", "1", "3", "4", "5", "6", "7", "8", ""]);
+    let response = submit(addr, "PUT", "/edit", "editor", &form).await;
+
+    assert!(response.contains("tile 1 is there 2 times"));
+    assert!(response.contains("tile 2 is missing"));
+}
+
+#[tokio::test]
+async fn explains_an_unsolvable_board() {
+    let addr = spawn_app("unsolvable").await;
+    let form = cells(3, 3, &["2", "1", "3", "4", "5", "6", "7", "8", ""]);
+    let response = submit(addr, "PUT", "/edit", "editor", &form).await;
+
+    assert!(response.contains("can never be solved"));
+    // nothing to play or solve until it's fixed
+    assert!(response.contains(r#"hx-post="/edit/play" disabled"#));
+}
+
+// the row shown is the row that's added up, and the goal's the same count
+#[tokio::test]
+async fn explains_an_even_width_board_with_the_rows_it_shows() {
+    let addr = spawn_app("even-width").await;
+    let form = cells(2, 2, &["1", "2", "", "3"]);
+    let response = submit(addr, "PUT", "/edit", "editor", &form).await;
+    assert!(
+        response.contains("0 inversions plus the blank on row 2 makes 2."),
+        "{}",
+        response
+    );
+    assert!(response.contains("the goal has 2 (blank on the last row"));
+    assert!(response.contains("so it can be solved"));
+
+    let form = cells(2, 2, &["2", "1", "", "3"]);
+    let response = submit(addr, "PUT", "/edit", "editor", &form).await;
+    assert!(response.contains("1 inversions plus the blank on row 2 makes 3."));
+    assert!(response.contains("can never be solved"));
+}
+
+#[tokio::test]
+async fn plays_the_edited_board_in_the_session() {
+    let addr = spawn_app("play").await;
+    let form = cells(2, 3, &["1", "2", "3", "4", "", "5"]);
+    let response = submit(addr, "POST", "/edit/play", "editor", &form).await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+    // the websocket picks up the same session's game
+    let mut socket = common::connect(addr, "/ws", "session=editor").await;
+    let message = recv(&mut socket).await;
+
+    assert_eq!(message["cells"], serde_json::json!([1, 2, 3, 4, 0, 5]));
+    assert_eq!(message["cols"], 3);
+    assert_eq!(message["distance"], 1);
+}
+
+#[tokio::test]
+async fn solving_an_edited_board_records_a_personal_best() {
+    let addr = spawn_app("bests").await;
+    let form = cells(2, 2, &["1", "2", "", "3"]);
+    submit(addr, "POST", "/edit/play", "solver", &form).await;
+
+    let solved = submit(addr, "GET", "/slide?idx=3", "solver", "").await;
+    assert!(solved.contains("first 2x2 easy solve"));
+    assert!(solved.contains(r#"data-running="false""#));
+
+    let bests = submit(addr, "GET", "/bests", "solver", "").await;
+    assert!(bests.contains("<td> 2x2 </td>"));
+    // someone else's session has nothing solved
+    let other = submit(addr, "GET", "/bests", "someone-else", "").await;
+    assert!(other.contains("nothing solved yet"));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::{spawn_app, FORM};
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    common::request(addr, "GET", path, "session=player", FORM, "").await
+}
+
+// the tile the hint lights up
+fn hinted(page: &str) -> usize {
+    let (_, after) = page.split_once("btn-accent").unwrap();
+    let (_, idx) = after.split_once("/slide?idx=").unwrap();
+    idx.split('"').next().unwrap().parse().unwrap()
+}
+
+// where the blank is, its button reads 0
+fn blank(page: &str) -> usize {
+    page.split("/slide?idx=")
+        .skip(1)
+        .find_map(|tile| {
+            let (idx, rest) = tile.split_once('"').unwrap();
+            let (_, label) = rest.split_once('>').unwrap();
+            let (label, _) = label.split_once("</button>").unwrap();
+            (label.trim() == "0").then(|| idx.parse().unwrap())
+        })
+        .unwrap()
+}
+
+fn distance(page: &str) -> usize {
+    let (before, _) = page.split_once(" moves away from solving it").unwrap();
+    before.rsplit("<p> ").next().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn says_whether_each_move_got_closer() {
+    let addr = spawn_app("feedback-closer").await;
+    // far enough out that neither move below solves it
+    let hint = loop {
+        get(addr, "/first_iteration").await;
+        let hint = get(addr, "/hint").await;
+        if !hint.contains("solved in ") && distance(&hint) > 1 {
+            break hint;
+        }
+    };
+    let start = distance(&hint);
+
+    let closer = get(addr, &format!("/slide?idx={}", hinted(&hint))).await;
+    assert!(closer.contains("closer to the goal"), "{}", closer);
+    assert_eq!(distance(&closer), start - 1);
+    assert!(closer.contains("moves: 1 | efficiency: 100%"), "{}", closer);
+
+    // sliding the same tile back undoes it
+    let away = get(addr, &format!("/slide?idx={}", blank(&hint))).await;
+    assert!(away.contains("further from the goal"), "{}", away);
+    assert_eq!(distance(&away), start);
+    assert!(away.contains("moves: 2 | efficiency: 0%"), "{}", away);
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{spawn_app, FORM};
+
+async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    common::request(addr, "GET", path, "session=player", FORM, "").await
+}
+
+// the tile the hint lights up
+fn hinted(page: &str) -> usize {
+    let (_, after) = page.split_once("btn-accent").unwrap();
+    let (_, idx) = after.split_once("/slide?idx=").unwrap();
+    idx.split('"').next().unwrap().parse().unwrap()
+}
+
+// what the hint says is left, 0 once it's solved
+fn distance(page: &str) -> usize {
+    if page.contains("solved in ") {
+        return 0;
+    }
+    let (before, _) = page.split_once(" moves away from solving it").unwrap();
+    before.rsplit("<p> ").next().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn every_hinted_move_is_one_closer() {
+    let addr = spawn_app("hint-follow").await;
+    get(addr, "/first_iteration").await;
+
+    let mut hint = get(addr, "/hint").await;
+    let mut left = distance(&hint);
+    while left > 0 {
+        get(addr, &format!("/slide?idx={}", hinted(&hint))).await;
+        hint = get(addr, "/hint").await;
+        assert_eq!(distance(&hint), left - 1, "{}", hint);
+        left -= 1;
+    }
+    assert!(!hint.contains("btn-accent"), "{}", hint);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{spawn_app, FORM, START};
+
+// the whole response, cookies sent as they are
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    cookies: &str,
+    body: &str,
+) -> String {
+    common::request(addr, method, path, cookies, FORM, body).await
+}
+
+// 1 2 3 / 4 5 6 / _ 7 8 is two slides from solved, this takes four: 7 over,
+// back again, then the two that solve it
+async fn solve_the_long_way(addr: std::net::SocketAddr, cookies: &str) {
+    request(addr, "POST", "/edit/play", cookies, START).await;
+    for idx in [7, 6, 7, 8] {
+        request(addr, "GET", &format!("/slide?idx={}", idx), cookies, "").await;
+    }
+}
+
+#[tokio::test]
+async fn replays_a_game_against_the_optimal_path() {
+    let addr = spawn_app("replays").await;
+    solve_the_long_way(addr, "session=ada").await;
+
+    let listed = request(addr, "GET", "/history", "session=ada", "").await;
+    assert!(listed.contains("3x3"));
+    assert!(listed.contains(r#"href="/history/1""#), "{}", listed);
+
+    let start = request(addr, "GET", "/history/1", "session=ada", "").await;
+    assert!(start.contains("1 / 5"));
+    assert!(start.contains("0 moves"));
+    assert!(start.contains("0 of 2 moves"));
+    assert!(start.contains(r#"hx-get="/history/1?step=2&interval=100&playing=false""#));
+
+    // back where it started after two moves, the optimal path's solved by then
+    let detour = request(addr, "GET", "/history/1?step=3", "session=ada", "").await;
+    assert!(detour.contains("2 moves"));
+    assert!(detour.contains("2 of 2 moves"));
+    assert!(detour.contains(r#"id="playback-tile-7" class="btn btn-lg btn-primary no-animation playback-tile" style="--row: 2; --col: 1""#));
+    assert!(detour.contains(r#"id="optimal-tile-7" class="btn btn-lg btn-secondary no-animation playback-tile" style="--row: 2; --col: 0""#));
+
+    let end = request(addr, "GET", "/history/1?step=99", "session=ada", "").await;
+    assert!(end.contains("5 / 5"));
+    assert!(end.contains("solved in 4 moves, the optimal path takes 2"));
+}
+
+#[tokio::test]
+async fn playing_streams_the_rest_of_the_replay() {
+    let addr = spawn_app("streams").await;
+    solve_the_long_way(addr, "session=bea").await;
+
+    let playing = request(
+        addr,
+        "GET",
+        "/history/1?step=2&playing=true",
+        "session=bea",
+        "",
+    )
+    .await;
+    assert!(playing.contains(r#"sse-connect="/history/1/stream?from=2&interval=100""#));
+
+    let path = "/history/1/stream?from=2&interval=10";
+    let streamed = request(addr, "GET", path, "session=bea", "").await;
+    assert_eq!(streamed.matches("event: step").count(), 3);
+    // the last frame takes the stream's place, which closes it
+    assert_eq!(streamed.matches("hx-swap-oob").count(), 1);
+}
+
+#[tokio::test]
+async fn replays_are_only_public_on_accounts() {
+    let addr = spawn_app("public").await;
+    solve_the_long_way(addr, "session=cal").await;
+
+    let other = request(addr, "GET", "/history/1", "session=dee", "").await;
+    assert!(other.starts_with("HTTP/1.1 404"));
+    let listed = request(addr, "GET", "/history", "session=dee", "").await;
+    assert!(listed.contains("nothing solved yet"));
+
+    let form = "name=cal&password=correct+horse&keep=on";
+    request(addr, "POST", "/register", "session=cal", form).await;
+    let public = request(addr, "GET", "/u/CAL/history/1", "session=dee", "").await;
+    assert!(public.starts_with("HTTP/1.1 200"), "{}", public);
+    assert!(public.contains(r#"hx-get="/u/cal/history/1?step=2"#));
+    let profile = request(addr, "GET", "/u/cal", "session=dee", "").await;
+    assert!(profile.contains(r#"href="/u/cal/history/1""#));
+    let missing = request(addr, "GET", "/u/nobody/history/1", "session=dee", "").await;
+    assert!(missing.starts_with("HTTP/1.1 404"));
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{spawn_app, spawn_database, Db, JSON};
+use serde_json::Value;
+use slidin_puzzle::slayin::Board;
+
+// (status, body) for a request made as the given session
+async fn send(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    content_type: &str,
+    body: &str,
+) -> (u16, String) {
+    let cookies = format!("session={}", session);
+    let (status, _, body) = common::send(addr, method, path, &cookies, content_type, body).await;
+    (status, body)
+}
+
+async fn submit(addr: std::net::SocketAddr, body: &str) -> (u16, Value) {
+    let (status, body) = send(addr, "POST", "/api/jobs", "ada", JSON, body).await;
+    (status, serde_json::from_str(&body).unwrap())
+}
+
+async fn job(addr: std::net::SocketAddr, id: i64) -> Value {
+    let path = format!("/api/jobs/{}", id);
+    let (_, body) = send(addr, "GET", &path, "ada", "text/plain", "").await;
+    serde_json::from_str(&body).unwrap()
+}
+
+// polled until a worker's done with it one way or the other
+async fn finished(addr: std::net::SocketAddr, id: i64) -> Value {
+    for _ in 0..200 {
+        let job = job(addr, id).await;
+        if job["status"] == "done" || job["status"] == "gave_up" {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("job {} never finished", id);
+}
+
+// 1 2 3 / 4 5 6 / _ 7 8, two slides from solved
+fn start() -> Board {
+    Board::with_size(vec![1, 2, 3, 4, 5, 6, 0, 7, 8], 3, 3)
+}
+
+// 1 2 3 / 4 5 6 / 8 7 _, two tiles swapped so no amount of sliding fixes it
+fn unsolvable() -> Board {
+    Board::with_size(vec![1, 2, 3, 4, 5, 6, 8, 7, 0], 3, 3)
+}
+
+// 8 6 7 / 2 5 4 / 3 _ 1, 31 moves out, more than a tiny budget reaches
+fn far() -> Board {
+    Board::with_size(vec![8, 6, 7, 2, 5, 4, 3, 0, 1], 3, 3)
+}
+
+#[tokio::test]
+async fn solves_in_the_background_and_caches_the_path() {
+    let addr = spawn_app("solve").await;
+
+    let (status, queued) = submit(addr, &format!(r#"{{"board": "{}"}}"#, start().code())).await;
+    assert_eq!(status, 202);
+    let id = queued["id"].as_i64().unwrap();
+
+    let done = finished(addr, id).await;
+    assert_eq!(done["status"], "done");
+    assert_eq!(done["moves"], 2);
+    assert_eq!(done["path"][0], start().code());
+    assert!(done["finished_at"].is_string());
+
+    // playing it after finds the path the job left behind
+    let form = "rows=3&cols=3&cell=1&cell=2&cell=3&cell=4&cell=5&cell=6&cell=&cell=7&cell=8";
+    let form_type = "application/x-www-form-urlencoded";
+    send(addr, "POST", "/edit/play", "bob", form_type, form).await;
+    send(addr, "GET", "/rest_iterations", "bob", "text/plain", "").await;
+    let (_, stats) = send(addr, "GET", "/api/solver/cache", "bob", "text/plain", "").await;
+    let stats: Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["misses"], 0);
+    assert_eq!(stats["memory_hits"], 1);
+}
+
+#[tokio::test]
+async fn streams_updates_until_the_job_finishes() {
+    let addr = spawn_app("events").await;
+    let (_, queued) = submit(addr, &format!(r#"{{"board": "{}"}}"#, far().code())).await;
+    let path = format!("/api/jobs/{}/events", queued["id"]);
+
+    // the stream ends by itself once it's sent the finished job
+    let (status, events) = tokio::time::timeout(
+        Duration::from_secs(30),
+        send(addr, "GET", &path, "ada", "text/plain", ""),
+    )
+    .await
+    .unwrap();
+    assert_eq!(status, 200);
+    assert!(events.contains("event: done"), "{}", events);
+    assert!(events.contains(r#""moves":31"#), "{}", events);
+}
+
+#[tokio::test]
+async fn gives_up_when_the_budget_runs_out() {
+    let addr = spawn_app("budget").await;
+    let body = format!(r#"{{"board": "{}", "budget": 100}}"#, far().code());
+    let (_, queued) = submit(addr, &body).await;
+
+    let done = finished(addr, queued["id"].as_i64().unwrap()).await;
+    assert_eq!(done["status"], "gave_up");
+    assert_eq!(done["budget"], 100);
+    assert!(done["nodes"].as_i64().unwrap() > 100);
+    assert!(done["path"].is_null());
+}
+
+#[tokio::test]
+async fn refuses_boards_it_cant_solve() {
+    let addr = spawn_app("refuse").await;
+
+    let (status, error) = submit(addr, r#"{"board": "???"}"#).await;
+    assert_eq!(status, 422);
+    assert!(error["error"]
+        .as_str()
+        .unwrap()
+        .contains("isn't a board code"));
+
+    let body = format!(r#"{{"board": "{}"}}"#, unsolvable().code());
+    assert_eq!(submit(addr, &body).await.0, 422);
+
+    let body = format!(r#"{{"board": "{}", "metric": "diagonal"}}"#, start().code());
+    assert_eq!(submit(addr, &body).await.0, 422);
+
+    let (status, _) = send(addr, "GET", "/api/jobs/999", "ada", "text/plain", "").await;
+    assert_eq!(status, 404);
+}
+
+#[tokio::test]
+async fn picks_up_jobs_a_restart_cut_off() {
+    let db = Db::new("resume");
+    let (_, pool) = spawn_database(&db).await;
+    // what a server killed mid-solve leaves behind
+    sqlx::query(
+        "INSERT INTO jobs (session, board, metric, budget, status, nodes)
+         VALUES ('ada', ?, 'single_tile', 1000, 'running', 500)",
+    )
+    .bind(start().code())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (addr, _) = spawn_database(&db).await;
+    let done = finished(addr, 1).await;
+    assert_eq!(done["status"], "done");
+    assert_eq!(done["moves"], 2);
+}
+
+#[tokio::test]
+async fn only_the_submitting_session_sees_a_job() {
+    let addr = spawn_app("private").await;
+    let (_, queued) = submit(addr, &format!(r#"{{"board": "{}"}}"#, start().code())).await;
+    let id = queued["id"].as_i64().unwrap();
+    finished(addr, id).await;
+
+    for path in [
+        format!("/api/jobs/{}", id),
+        format!("/api/jobs/{}/events", id),
+    ] {
+        let (status, body) = send(addr, "GET", &path, "bob", "text/plain", "").await;
+        assert_eq!(status, 404);
+        assert!(body.contains(&format!("no job {}", id)), "{}", body);
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{spawn_app, FORM};
+
+async fn request(addr: std::net::SocketAddr, method: &str, path: &str, body: &str) -> String {
+    common::request(addr, method, path, "session=keys", FORM, body).await
+}
+
+// 1 2 3 / 4 _ 6 / 7 5 8, the blank in the middle
+async fn play(addr: std::net::SocketAddr) {
+    let form = "rows=3&cols=3&cell=1&cell=2&cell=3&cell=4&cell=&cell=6&cell=7&cell=5&cell=8";
+    let response = request(addr, "POST", "/edit/play", form).await;
+    assert!(response.contains(r#"data-blank="4""#), "{}", response);
+}
+
+#[tokio::test]
+async fn arrows_slide_tiles_either_way_round() {
+    let addr = spawn_app("conventions").await;
+    play(addr).await;
+
+    // up pushes the 5 under the blank up into it
+    let up = request(addr, "GET", "/slide/key?dir=up", "").await;
+    assert!(up.contains(r#"data-blank="7""#));
+    assert!(up.contains(r#"aria-label="tile 5, row 2 column 2""#));
+    assert!(up.contains(r#"aria-label="blank, row 3 column 2""#));
+    assert!(up.contains("closer to the goal"));
+
+    // moving the blank up puts the 5 back where it was
+    let back = request(addr, "GET", "/slide/key?dir=up&convention=blank", "").await;
+    assert!(back.contains(r#"data-blank="4""#));
+    assert!(back.contains("further from the goal"));
+    assert!(back.contains("moves: 2"));
+}
+
+#[tokio::test]
+async fn keys_off_the_edge_dont_move_anything() {
+    let addr = spawn_app("edge").await;
+    play(addr).await;
+    request(addr, "GET", "/slide/key?dir=up", "").await;
+
+    // nothing under the blank on the bottom row
+    let stuck = request(addr, "GET", "/slide/key?dir=up", "").await;
+    assert!(stuck.contains(r#"data-blank="7""#));
+    assert!(stuck.contains("that move isn't possible"));
+    assert!(stuck.contains("moves: 1"));
+
+    // clicks get the same checks
+    let clicked = request(addr, "GET", "/slide?idx=0", "").await;
+    assert!(clicked.contains("that move isn't possible"));
+
+    let nonsense = request(addr, "GET", "/slide/key?dir=sideways", "").await;
+    assert!(nonsense.starts_with("HTTP/1.1 400"));
+}
+
+#[tokio::test]
+async fn without_a_game_the_board_routes_go_to_a_new_one() {
+    let addr = spawn_app("no-game").await;
+    for (method, path) in [
+        ("GET", "/slide?idx=1"),
+        ("GET", "/slide/key?dir=up"),
+        ("GET", "/hint"),
+        ("POST", "/metric"),
+    ] {
+        let body = if method == "POST" {
+            "metric=multi_tile"
+        } else {
+            ""
+        };
+        let response = request(addr, method, path, body).await;
+        assert!(response.starts_with("HTTP/1.1 303"), "{}", response);
+        assert!(response.contains("location: /first_iteration"));
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{spawn_app, FORM, JSON};
+use serde_json::Value;
+
+// (status, body) for a request made as the given session
+async fn send(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    content_type: &str,
+    body: &str,
+) -> (u16, String) {
+    let cookies = format!("session={}", session);
+    let (status, _, body) = common::send(addr, method, path, &cookies, content_type, body).await;
+    (status, body)
+}
+
+async fn submit(addr: std::net::SocketAddr, session: &str, history: &str) -> (u16, String) {
+    let body = format!(r#"{{"name": "ada", "history": {}}}"#, history);
+    send(addr, "POST", "/api/leaderboard", session, JSON, &body).await
+}
+
+// a 2x2 that's two slides from solved
+async fn play(addr: std::net::SocketAddr, session: &str) {
+    let form = "rows=2&cols=2&cell=1&cell=2&cell=3&cell=";
+    send(addr, "POST", "/edit/play", session, FORM, form).await;
+}
+
+#[tokio::test]
+async fn accepts_a_replayed_solve() {
+    let addr = spawn_app("accepts").await;
+    play(addr, "ada").await;
+    // already solved, so go round the long way: blank left, then back right
+    send(addr, "GET", "/slide?idx=2", "ada", FORM, "").await;
+    send(addr, "GET", "/slide?idx=3", "ada", FORM, "").await;
+
+    let (status, _) = submit(addr, "ada", "[2, 3]").await;
+    assert_eq!(status, 201);
+
+    let path = "/api/leaderboard?rows=2&cols=2&difficulty=easy&metric=moves";
+    let (_, body) = send(addr, "GET", path, "someone", "text/plain", "").await;
+    let entries: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(entries[0]["name"], "ada");
+    assert_eq!(entries[0]["moves"], 2);
+    assert_eq!(entries[0]["optimal"], 0);
+}
+
+#[tokio::test]
+async fn rejects_unsolved_and_made_up_histories() {
+    let addr = spawn_app("rejects").await;
+    play(addr, "bob").await;
+    send(addr, "GET", "/slide?idx=2", "bob", FORM, "").await;
+
+    let (status, body) = submit(addr, "bob", "[2]").await;
+    assert_eq!(status, 422);
+    assert!(body.contains("solve it first"));
+
+    send(addr, "GET", "/slide?idx=3", "bob", FORM, "").await;
+    let (status, body) = submit(addr, "bob", "[1, 3]").await;
+    assert_eq!(status, 422);
+    assert!(body.contains("not how the game went"));
+
+    assert_eq!(submit(addr, "bob", "[2, 3]").await.0, 201);
+    let (status, body) = submit(addr, "bob", "[2, 3]").await;
+    assert_eq!(status, 422);
+    assert!(body.contains("already on the leaderboard"));
+}
+
+#[tokio::test]
+async fn slides_after_the_solve_dont_count() {
+    let addr = spawn_app("after").await;
+    play(addr, "cy").await;
+    for idx in [2, 3, 2] {
+        let path = format!("/slide?idx={}", idx);
+        send(addr, "GET", &path, "cy", FORM, "").await;
+    }
+
+    assert_eq!(submit(addr, "cy", "[2, 3, 2]").await.0, 201);
+    let path = "/api/leaderboard?rows=2&cols=2&difficulty=easy&metric=moves";
+    let (_, body) = send(addr, "GET", path, "someone", "text/plain", "").await;
+    let entries: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(entries[0]["name"], "ada");
+    assert_eq!(entries[0]["moves"], 2);
+}
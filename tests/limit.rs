@@ -0,0 +1,234 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{connect, exchange, recv, send_json, split, FORM, START};
+use serde_json::json;
+use slidin_puzzle::config::{Backend, Config};
+use slidin_puzzle::slayin::Board;
+
+// served the way main does it, so the limits see the peer's address
+async fn spawn_app(config: Config) -> std::net::SocketAddr {
+    let config = Config {
+        store: Backend::Memory,
+        ..config
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        slidin_puzzle::serve(listener, &config, std::future::pending()).await;
+    });
+    addr
+}
+
+// (status, head, body), head being the status line and headers lowercased.
+// headers go in ahead of the form's content type
+async fn send(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    headers: &str,
+    body: &str,
+) -> (u16, String, String) {
+    let head = format!(
+        "{} {} HTTP/1.1\r\nCookie: session={}\r\n{}Content-Type: {}",
+        method, path, session, headers, FORM
+    );
+    let (status, head, body) = split(&exchange(addr, &head, body.as_bytes()).await);
+    (status, head.to_lowercase(), body)
+}
+
+async fn get(addr: std::net::SocketAddr, path: &str, session: &str) -> (u16, String, String) {
+    send(addr, "GET", path, session, "", "").await
+}
+
+fn form(board: &Board) -> String {
+    let mut form = format!("rows={}&cols={}", board.rows, board.cols);
+    for cell in &board.cells {
+        match cell {
+            0 => form.push_str("&cell="),
+            cell => form.push_str(&format!("&cell={}", cell)),
+        }
+    }
+    form
+}
+
+// the 5x5 backwards, solvable but far more than a request's budget gets through
+fn backwards() -> Board {
+    let mut cells: Vec<i32> = (1..25).rev().collect();
+    cells.push(0);
+    Board::with_size(cells, 5, 5)
+}
+
+#[tokio::test]
+async fn a_session_that_keeps_going_gets_told_to_slow_down() {
+    let addr = spawn_app(Config {
+        per_session: 3,
+        ..Config::default()
+    })
+    .await;
+
+    send(addr, "POST", "/edit/play", "ada", "", START).await;
+    assert_eq!(get(addr, "/hint", "ada").await.0, 200);
+    assert_eq!(get(addr, "/hint", "ada").await.0, 200);
+
+    let (status, head, body) = get(addr, "/hint", "ada").await;
+    assert_eq!(status, 429);
+    assert!(head.contains("retry-after: "), "{}", head);
+    assert!(body.contains("slow down a little"), "{}", body);
+    assert!(body.contains("<html"), "{}", body);
+
+    // htmx gets a notice to put above the board instead of the board going
+    let (status, head, body) = send(addr, "GET", "/hint", "ada", "HX-Request: true\r\n", "").await;
+    assert_eq!(status, 429);
+    assert!(head.contains("hx-retarget: #notice"), "{}", head);
+    assert!(
+        body.contains("alert") && !body.contains("<html"),
+        "{}",
+        body
+    );
+
+    // everyone else is fine, and the pages that don't solve aren't counted
+    send(addr, "POST", "/edit/play", "bob", "", START).await;
+    assert_eq!(get(addr, "/hint", "bob").await.0, 200);
+    assert_eq!(get(addr, "/bests", "ada").await.0, 200);
+}
+
+#[tokio::test]
+async fn an_address_gets_limited_across_sessions() {
+    let addr = spawn_app(Config {
+        per_ip: 2,
+        ..Config::default()
+    })
+    .await;
+
+    assert_eq!(get(addr, "/edit", "ada").await.0, 200);
+    assert_eq!(get(addr, "/edit", "bob").await.0, 200);
+    assert_eq!(get(addr, "/edit", "cy").await.0, 429);
+}
+
+#[tokio::test]
+async fn boards_bigger_than_the_server_takes_are_refused() {
+    let addr = spawn_app(Config {
+        max_tiles: 9,
+        ..Config::default()
+    })
+    .await;
+
+    let four = Board::with_size(Board::goal(4, 4), 4, 4);
+    let (status, _, body) = send(addr, "PUT", "/edit", "ada", "", &form(&four)).await;
+    assert_eq!(status, 413);
+    assert!(
+        body.contains("a 4x4 board is more than this server solves"),
+        "{}",
+        body
+    );
+    assert_eq!(send(addr, "PUT", "/edit", "ada", "", START).await.0, 200);
+
+    let path = format!("/p/{}", four.code());
+    assert_eq!(get(addr, &path, "ada").await.0, 413);
+
+    let job = format!(r#"{{"board": "{}"}}"#, four.code());
+    let json = "Content-Type: application/json\r\n";
+    let (status, _, body) = send(addr, "POST", "/api/jobs", "ada", json, &job).await;
+    assert_eq!(status, 413);
+    let error: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("9 tiles at most"));
+}
+
+#[tokio::test]
+async fn bodies_over_the_limit_are_refused() {
+    let addr = spawn_app(Config {
+        max_body: 256,
+        ..Config::default()
+    })
+    .await;
+
+    let padded = format!("{}&name={}", START, "x".repeat(512));
+    let (status, _, body) = send(addr, "POST", "/edit/play", "ada", "", &padded).await;
+    assert_eq!(status, 413);
+    assert!(body.contains("more than the server takes"), "{}", body);
+    assert_eq!(
+        send(addr, "POST", "/edit/play", "ada", "", START).await.0,
+        200
+    );
+}
+
+#[tokio::test]
+async fn solves_past_the_cap_are_turned_away() {
+    let addr = spawn_app(Config {
+        max_solves: 1,
+        ..Config::default()
+    })
+    .await;
+
+    // long enough to get in while it's solving, short enough not to hold the
+    // tests up. the only solve in here that gets near it
+    slidin_puzzle::slayin::set_node_budget(100_000);
+    let slow = form(&backwards());
+    let solving = tokio::spawn(async move { send(addr, "PUT", "/edit", "ada", "", &slow).await });
+    // held until the first one's done with its only slot
+    let mut busy = None;
+    while !solving.is_finished() {
+        let (status, _, body) = get(addr, "/edit", "bob").await;
+        if status == 429 {
+            busy = Some(body);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    let busy = busy.expect("the second request got a slot while the first was solving");
+    assert!(busy.contains("busy with other boards"), "{}", busy);
+
+    assert_eq!(solving.await.unwrap().0, 200);
+    assert_eq!(get(addr, "/edit", "bob").await.0, 200);
+}
+
+#[tokio::test]
+async fn websocket_messages_count_like_requests() {
+    let addr = spawn_app(Config {
+        per_session: 3,
+        ..Config::default()
+    })
+    .await;
+    let mut socket = connect(addr, "/ws", "").await;
+    assert_eq!(recv(&mut socket).await["type"], "board");
+
+    // connecting took the first one
+    for _ in 0..2 {
+        send_json(&mut socket, json!({ "type": "hint" })).await;
+        assert_eq!(recv(&mut socket).await["type"], "hint");
+    }
+    send_json(&mut socket, json!({ "type": "hint" })).await;
+    let refused = recv(&mut socket).await;
+    assert_eq!(refused["type"], "error");
+    assert!(
+        refused["message"]
+            .as_str()
+            .unwrap()
+            .contains("slow down a little"),
+        "{}",
+        refused
+    );
+}
+
+#[tokio::test]
+async fn only_the_routes_that_solve_wait_on_the_solver() {
+    let addr = spawn_app(Config {
+        max_solves: 0,
+        ..Config::default()
+    })
+    .await;
+
+    // no slots at all, so everything that solves is busy
+    assert_eq!(get(addr, "/daily", "ada").await.0, 429);
+    assert_eq!(send(addr, "POST", "/race", "ada", "", "").await.0, 429);
+
+    // signing up is only counted
+    let form = "name=ada&password=correct+horse";
+    assert_eq!(
+        send(addr, "POST", "/register", "ada", "", form).await.0,
+        303
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{spawn_config, FORM, START};
+use slidin_puzzle::config::{Backend, Config};
+
+async fn spawn_app() -> std::net::SocketAddr {
+    let config = Config {
+        store: Backend::Memory,
+        ..Config::default()
+    };
+    spawn_config(&config).await
+}
+
+// the whole response, headers and all
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    body: &str,
+) -> String {
+    let cookies = format!("session={}", session);
+    common::request(addr, method, path, &cookies, FORM, body).await
+}
+
+// the value on the line that starts with name, 0 if it hasn't been counted yet
+fn sample(metrics: &str, name: &str) -> f64 {
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .map_or(0.0, |value| value.parse().unwrap())
+}
+
+#[tokio::test]
+async fn counts_games_moves_solves_and_requests() {
+    let addr = spawn_app().await;
+    // the counters are shared by every app in this process
+    let before = request(addr, "GET", "/metrics", "anyone", "").await;
+
+    request(addr, "POST", "/edit/play", "ada", START).await;
+    request(addr, "GET", "/rest_iterations", "ada", "").await;
+    request(addr, "GET", "/slide?idx=7", "ada", "").await;
+    request(addr, "GET", "/slide?idx=8", "ada", "").await;
+    request(addr, "GET", "/p/nothing-here", "ada", "").await;
+
+    let after = request(addr, "GET", "/metrics", "anyone", "").await;
+    assert!(
+        after.contains("content-type: text/plain; version=0.0.4"),
+        "{}",
+        after
+    );
+    let counted = |name: &str| sample(&after, name) - sample(&before, name);
+
+    assert_eq!(counted("slidin_games_started_total"), 1.0);
+    assert_eq!(counted("slidin_games_finished_total"), 1.0);
+    assert_eq!(counted("slidin_moves_total{mode=\"solo\"}"), 2.0);
+    assert!(counted("slidin_solver_runs_total{algorithm=\"a_star\",metric=\"single_tile\",outcome=\"solved\"}") >= 1.0);
+    assert!(counted("slidin_solver_duration_seconds_count{algorithm=\"a_star\"}") >= 1.0);
+    assert!(counted("slidin_solver_nodes_expanded_count{algorithm=\"a_star\"}") >= 1.0);
+    // the solve, then the board after each slide scored against the cache
+    assert_eq!(
+        counted("slidin_solution_cache_lookups_total{result=\"miss\"}"),
+        3.0
+    );
+
+    // by the route they matched rather than the path asked for
+    assert!(
+        after.contains("route=\"/slide\",status=\"200\"} "),
+        "{}",
+        after
+    );
+    assert!(after.contains("route=\"/p/:code\""), "{}", after);
+    assert!(
+        after.contains("slidin_http_request_duration_seconds_bucket{route=\"/slide\",le=\"+Inf\"}")
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use common::{exchange, spawn_config, Db};
+use slidin_puzzle::config::Config;
+
+// where a test's uploads go, gone again when it's dropped
+struct Pictures(PathBuf);
+
+impl Drop for Pictures {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// the server keeps db and the pictures going as long as the test holds them
+async fn spawn_app(name: &str) -> (std::net::SocketAddr, Db, Pictures) {
+    let db = Db::new(name);
+    let pictures = Pictures(std::env::temp_dir().join(format!(
+        "slidin-puzzle-{}-{}-pictures",
+        name,
+        std::process::id()
+    )));
+    let config = Config {
+        database_url: db.url(),
+        pictures: pictures.0.clone(),
+        ..Config::default()
+    };
+    (spawn_config(&config).await, db, pictures)
+}
+
+async fn upload(addr: std::net::SocketAddr, session: &str, file: &[u8]) -> String {
+    let mut body = b"--boundary\r\n\
+        Content-Disposition: form-data; name=\"picture\"; filename=\"picture.png\"\r\n\
+        Content-Type: image/png\r\n\r\n"
+        .to_vec();
+    body.extend_from_slice(file);
+    body.extend_from_slice(b"\r\n--boundary--\r\n");
+
+    let head = format!(
+        "POST /picture HTTP/1.1\r\nCookie: session={}\r\n\
+         Content-Type: multipart/form-data; boundary=boundary",
+        session
+    );
+    exchange(addr, &head, &body).await
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let image = image::RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+    });
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, image::ImageOutputFormat::Png)
+        .unwrap();
+    bytes.into_inner()
+}
+
+#[tokio::test]
+async fn plays_an_uploaded_picture() {
+    let (addr, _db, pictures) = spawn_app("upload").await;
+    let response = upload(addr, "picture", &png(300, 200)).await;
+    assert!(response.starts_with("HTTP/1.1 303"));
+    assert!(response.contains("location: /first_iteration"));
+
+    let head = "GET /first_iteration HTTP/1.1\r\nCookie: session=picture";
+    let board = exchange(addr, head, b"").await;
+    let tile = board
+        .split(r#"<img src=""#)
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap()
+        .to_owned();
+    assert_eq!(board.matches("<img src=").count(), 8);
+    assert!(tile.contains("/3x3/"));
+
+    // kept under a token nobody could guess rather than a count
+    let token = tile.split('/').nth(2).unwrap();
+    assert_eq!(token.len(), 32, "{}", tile);
+    assert!(pictures.0.join(token).join("3x3").join("1.png").exists());
+
+    let served = exchange(addr, &format!("GET {} HTTP/1.1", tile), b"").await;
+    assert!(served.starts_with("HTTP/1.1 200"));
+    assert!(served.contains("content-type: image/png"));
+}
+
+#[tokio::test]
+async fn refuses_what_isnt_a_picture() {
+    let (addr, _db, _pictures) = spawn_app("not-a-picture").await;
+    let response = upload(addr, "not-a-picture", b"just some text").await;
+
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("that&#x27;s not a picture"));
+}
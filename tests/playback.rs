@@ -0,0 +1,82 @@
+mod common;
+
+use common::{spawn_app, FORM, START};
+
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    body: &str,
+) -> String {
+    let cookies = format!("session={}", session);
+    common::request(addr, method, path, &cookies, FORM, body).await
+}
+
+// 1 2 3 / 4 5 6 / _ 7 8, solved in two slides so three boards to play back
+async fn solve(addr: std::net::SocketAddr, session: &str) -> String {
+    request(addr, "POST", "/edit/play", session, START).await;
+    request(addr, "GET", "/rest_iterations?index=0", session, "").await
+}
+
+#[tokio::test]
+async fn steps_through_the_solution() {
+    let addr = spawn_app("steps").await;
+    let started = solve(addr, "stepper").await;
+    assert!(started.contains("sse-connect=\"/solve/stream?from=1"));
+    assert!(started.contains(r#"id="playback-tile-7" class="btn btn-lg btn-primary no-animation playback-tile" style="--row: 2; --col: 1""#));
+
+    // the tile keeps its id and only its position changes
+    let second = request(addr, "GET", "/playback?step=2", "stepper", "").await;
+    assert!(second.contains("2 / 3"));
+    assert!(second.contains(r#"id="playback-tile-7" class="btn btn-lg btn-primary no-animation playback-tile" style="--row: 2; --col: 0""#));
+    assert!(second.contains("/playback?step=1&interval=100&playing=false\"> &lt;"));
+    assert!(!second.contains("sse-connect"));
+
+    // past the end is the end
+    let last = request(addr, "GET", "/playback?step=99&interval=400", "stepper", "").await;
+    assert!(last.contains("3 / 3"));
+    assert!(last.contains("atoobees compelete"));
+    assert!(last.contains("400ms a step"));
+    assert!(!last.contains("RESUME"));
+}
+
+#[tokio::test]
+async fn playing_from_a_step_streams_the_rest() {
+    let addr = spawn_app("resume").await;
+    solve(addr, "player").await;
+
+    let resumed = request(addr, "GET", "/playback?step=2&playing=true", "player", "").await;
+    assert!(resumed.contains("sse-connect=\"/solve/stream?from=2&interval=100\""));
+    assert!(resumed.contains("PAUSE"));
+
+    // nothing solved in this session, so nothing to link to
+    let elsewhere = request(addr, "GET", "/playback?step=2", "someone-else", "").await;
+    assert!(elsewhere.starts_with("HTTP/1.1 303"));
+}
+
+#[tokio::test]
+async fn streams_the_steps_after_from_in_order_then_ends() {
+    let addr = spawn_app("stream").await;
+    solve(addr, "streamer").await;
+
+    // the stream ending is what lets the response finish
+    let path = "/solve/stream?from=1&interval=10";
+    let streamed = request(addr, "GET", path, "streamer", "").await;
+    assert!(streamed.contains("text/event-stream"), "{}", streamed);
+    let steps = streamed
+        .match_indices("aria-label=\"solution step ")
+        .map(|(at, label)| &streamed[at + label.len()..at + label.len() + 6])
+        .collect::<Vec<_>>();
+    assert_eq!(steps, ["2 of 3", "3 of 3"]);
+    assert_eq!(streamed.matches("event: step").count(), 2);
+    // only the last frame swaps the player out, which closes the event source
+    let (_, last) = streamed.rsplit_once("event: step").unwrap();
+    assert!(last.contains("hx-swap-oob"), "{}", last);
+    assert_eq!(streamed.matches("hx-swap-oob").count(), 1);
+
+    let path = "/solve/stream?from=2&interval=10";
+    let rest = request(addr, "GET", path, "streamer", "").await;
+    assert_eq!(rest.matches("event: step").count(), 1);
+    assert!(rest.contains("solution step 3 of 3"), "{}", rest);
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{spawn_app, FORM};
+use slidin_puzzle::slayin::{Board, MoveMetric};
+
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    body: &str,
+) -> String {
+    let cookies = format!("session={}", session);
+    common::request(addr, method, path, &cookies, FORM, body).await
+}
+
+// 1 2 3 / 4 5 6 / _ 7 8, a push of the bottom row from solved
+const PUSHED: &str = "rows=3&cols=3&cell=1&cell=2&cell=3&cell=4&cell=5&cell=6&cell=&cell=7&cell=8";
+
+#[test]
+fn a_row_push_is_one_move_under_the_multi_tile_count() {
+    let board = Board::with_size(vec![1, 2, 3, 4, 5, 6, 0, 7, 8], 3, 3);
+    assert_eq!(board.optimal_distance_with(MoveMetric::SingleTile), Some(2));
+    assert_eq!(board.optimal_distance_with(MoveMetric::MultiTile), Some(1));
+    // the far end of the push, sliding it takes the 7 along
+    assert_eq!(board.hint_with(MoveMetric::MultiTile), Some((8, 1)));
+
+    let mut pushed = board.clone();
+    assert_eq!(pushed.slide_line(8), Some(2));
+    assert!(pushed.is_solved());
+    // not in the blank's row or column
+    assert_eq!(board.clone().slide_line(4), None);
+}
+
+#[test]
+fn multi_tile_solutions_are_pushes_and_never_longer() {
+    for seed in 0..20 {
+        let board = Board::seeded_board(seed);
+        let single = board.optimal_distance_with(MoveMetric::SingleTile).unwrap();
+        let steps = board.solve_with(MoveMetric::MultiTile).unwrap();
+        assert!(steps.len() - 1 <= single);
+
+        let mut at = board.clone();
+        for step in &steps[1..] {
+            assert!(at.slide_line(Board::find_zero(step)).is_some());
+            assert_eq!(&at.cells, step);
+        }
+        assert!(at.is_solved());
+    }
+}
+
+#[tokio::test]
+async fn slides_count_the_way_the_game_was_set_to() {
+    let addr = spawn_app("counting").await;
+
+    request(addr, "POST", "/edit/play", "tiles", PUSHED).await;
+    let solved = request(addr, "GET", "/slide?idx=8", "tiles", "").await;
+    assert!(
+        solved.contains("solved in 2 moves, optimal was 2"),
+        "{}",
+        solved
+    );
+
+    let started = request(addr, "POST", "/edit/play", "pushes", PUSHED).await;
+    assert!(started.contains("optimal 2"));
+    let switched = request(addr, "POST", "/metric", "pushes", "metric=multi_tile").await;
+    assert!(switched.contains("optimal 1"));
+    let solved = request(addr, "GET", "/slide?idx=8", "pushes", "").await;
+    assert!(solved.contains("solved in 1 moves, optimal was 1"));
+
+    // too late once a move's been made
+    let refused = request(addr, "POST", "/metric", "pushes", "metric=single_tile").await;
+    assert!(refused.contains("already being counted"));
+
+    // the next game carries on counting pushes, and its best is its own
+    let next = request(addr, "POST", "/edit/play", "pushes", PUSHED).await;
+    assert!(next.contains("optimal 1"));
+    let bests = request(addr, "GET", "/bests", "pushes", "").await;
+    assert!(bests.contains("per push"));
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{recv, send_json, spawn_app, Socket, FORM};
+use serde_json::{json, Value};
+
+// POST /race answers with a redirect to the new room
+async fn create_room(addr: std::net::SocketAddr) -> String {
+    let response = common::request(addr, "POST", "/race", "", FORM, "").await;
+    let location = response
+        .lines()
+        .find_map(|line| line.strip_prefix("location: "))
+        .unwrap();
+    location.trim_start_matches("/race/").to_owned()
+}
+
+async fn join(addr: std::net::SocketAddr, code: &str, session: &str) -> Socket {
+    let path = format!("/race/{}/ws", code);
+    common::connect(addr, &path, &format!("session={}", session)).await
+}
+
+async fn send_move(socket: &mut Socket, idx: usize) {
+    send_json(socket, json!({ "type": "move", "idx": idx })).await;
+}
+
+fn player(state: &Value, seat: i64) -> &Value {
+    state["players"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|player| player["seat"] == seat)
+        .unwrap()
+}
+
+fn movable_tile(cells: &Value) -> usize {
+    let zero = cells
+        .as_array()
+        .unwrap()
+        .iter()
+        .position(|cell| cell == 0)
+        .unwrap();
+    if zero % 3 > 0 {
+        zero - 1
+    } else {
+        zero + 1
+    }
+}
+
+#[tokio::test]
+async fn both_players_see_each_others_moves() {
+    let addr = spawn_app("moves").await;
+    let code = create_room(addr).await;
+
+    let mut alice = join(addr, &code, "alice").await;
+    let waiting = recv(&mut alice).await;
+    assert_eq!(waiting["you"], 1);
+    assert_eq!(waiting["players"].as_array().unwrap().len(), 1);
+
+    // nobody moves before the opponent shows up
+    send_move(&mut alice, 0).await;
+    assert_eq!(recv(&mut alice).await["type"], "error");
+
+    let mut bob = join(addr, &code, "bob").await;
+    let state = recv(&mut bob).await;
+    assert_eq!(state["you"], 2);
+    assert_eq!(player(&state, 1)["cells"], player(&state, 2)["cells"]);
+    recv(&mut alice).await;
+
+    let idx = movable_tile(&player(&state, 2)["cells"]);
+    send_move(&mut bob, idx).await;
+    let seen_by_alice = recv(&mut alice).await;
+    assert_eq!(player(&seen_by_alice, 2)["moves"], 1);
+    assert_eq!(player(&seen_by_alice, 1)["moves"], 0);
+    assert_eq!(recv(&mut bob).await, {
+        let mut state = seen_by_alice.clone();
+        state["you"] = json!(2);
+        state
+    });
+}
+
+#[tokio::test]
+async fn third_player_is_turned_away() {
+    let addr = spawn_app("full").await;
+    let code = create_room(addr).await;
+
+    let mut alice = join(addr, &code, "alice").await;
+    recv(&mut alice).await;
+    let mut bob = join(addr, &code, "bob").await;
+    recv(&mut bob).await;
+    let mut carol = join(addr, &code, "carol").await;
+    let error = recv(&mut carol).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["message"], "the room is full");
+}
+
+#[tokio::test]
+async fn rejoining_keeps_the_seat() {
+    let addr = spawn_app("rejoin").await;
+    let code = create_room(addr).await;
+
+    let alice = join(addr, &code, "alice").await;
+    drop(alice);
+    let mut alice = join(addr, &code, "alice").await;
+    assert_eq!(recv(&mut alice).await["you"], 1);
+}
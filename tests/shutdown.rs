@@ -0,0 +1,79 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{Db, FORM};
+use slidin_puzzle::config::Config;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+
+// (status, body), without a session cookie since health checks won't have one
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> (u16, String) {
+    let (status, _, body) = common::send(addr, method, path, "", FORM, body).await;
+    (status, body)
+}
+
+// the 5x5 backwards, solvable but nowhere near a budget-free solver's reach
+fn backwards() -> String {
+    let mut body = String::from("rows=5&cols=5");
+    for tile in (1..25).rev() {
+        body.push_str(&format!("&cell={}", tile));
+    }
+    body.push_str("&cell=");
+    body
+}
+
+// one test, since shutting down stops the solver for everything in the process
+#[tokio::test]
+async fn drains_requests_and_stops_solves_on_shutdown() {
+    let db = Db::new("shutdown");
+    let config = Config {
+        database_url: db.url(),
+        ..Config::default()
+    };
+    // no budget to run out of, only shutting down stops it
+    slidin_puzzle::slayin::set_node_budget(usize::MAX);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        slidin_puzzle::serve(listener, &config, async {
+            stopped.await.unwrap();
+        })
+        .await;
+    });
+
+    assert_eq!(
+        request(addr, "GET", "/healthz", "").await,
+        (200, String::from("ok"))
+    );
+    assert_eq!(
+        request(addr, "GET", "/readyz", "").await,
+        (200, String::from("ready"))
+    );
+
+    let solving = tokio::spawn(async move { request(addr, "PUT", "/edit", &backwards()).await });
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    stop.send(()).unwrap();
+
+    // the solve gives up rather than holding the shutdown up forever
+    let (status, page) = tokio::time::timeout(Duration::from_secs(10), solving)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status, 200);
+    assert!(page.contains("too far from solved"), "{}", page);
+    tokio::time::timeout(Duration::from_secs(10), server)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // and nothing's listening any more
+    assert!(TcpStream::connect(addr).await.is_err());
+}
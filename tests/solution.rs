@@ -0,0 +1,67 @@
+mod common;
+
+use common::{spawn_app, FORM, START};
+
+async fn request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    body: &str,
+) -> String {
+    let cookies = format!("session={}", session);
+    common::request(addr, method, path, &cookies, FORM, body).await
+}
+
+#[tokio::test]
+async fn solves_from_where_the_player_got_to() {
+    let addr = spawn_app("from-here").await;
+    request(addr, "POST", "/edit/play", "halfway", START).await;
+    request(addr, "GET", "/slide?idx=7", "halfway", "").await;
+
+    // one slide left, so the solution is that board and the solved one
+    let solving = request(addr, "GET", "/rest_iterations?index=0", "halfway", "").await;
+    assert!(solving.contains("1 / 2"), "{}", solving);
+    assert!(solving.contains(r#"id="playback-tile-7" class="btn btn-lg btn-primary no-animation playback-tile" style="--row: 2; --col: 0""#));
+    let last = request(addr, "GET", "/solve?index=1", "halfway", "").await;
+    assert!(last.contains("atoobees compelete"));
+
+    // the game carries on from where it was, solving didn't touch it
+    let solved = request(addr, "GET", "/slide?idx=8", "halfway", "").await;
+    assert!(
+        solved.contains("solved in 2 moves, optimal was 2"),
+        "{}",
+        solved
+    );
+    // moves after that don't change what it was solved in
+    let after = request(addr, "GET", "/slide?idx=7", "halfway", "").await;
+    assert!(after.contains("moves: 3 "), "{}", after);
+    assert!(after.contains("solved in 2 moves, optimal was 2"));
+}
+
+#[tokio::test]
+async fn solving_again_doesnt_trip_over_the_last_solution() {
+    let addr = spawn_app("again").await;
+    request(addr, "POST", "/edit/play", "twice", START).await;
+
+    let first = request(addr, "GET", "/rest_iterations?index=0", "twice", "").await;
+    assert!(first.contains("1 / 3"));
+    let moved = request(addr, "GET", "/slide?idx=7", "twice", "").await;
+    assert!(moved.contains("moves: 1 "));
+
+    let second = request(addr, "GET", "/rest_iterations?index=0", "twice", "").await;
+    assert!(second.contains("1 / 2"));
+    let playback = request(addr, "GET", "/playback?step=2", "twice", "").await;
+    assert!(playback.contains("2 / 2"));
+    let moved = request(addr, "GET", "/slide?idx=8", "twice", "").await;
+    assert!(moved.contains("moves: 2 "));
+}
+
+#[tokio::test]
+async fn solving_without_a_game_starts_one() {
+    let addr = spawn_app("no-game").await;
+
+    let response = request(addr, "GET", "/rest_iterations?index=0", "fresh", "").await;
+    assert!(response.starts_with("HTTP/1.1 303"), "{}", response);
+    assert!(response.contains("location: /first_iteration"));
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::{spawn_config, FORM, JSON, START};
+use serde_json::Value;
+use slidin_puzzle::config::{Backend, Config};
+
+// no database file anywhere, everything lives in the server's memory
+async fn spawn_app() -> std::net::SocketAddr {
+    let config = Config {
+        store: Backend::Memory,
+        ..Config::default()
+    };
+    spawn_config(&config).await
+}
+
+// (status, body) for a request made as the given session
+async fn send(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    session: &str,
+    content_type: &str,
+    body: &str,
+) -> (u16, String) {
+    let cookies = format!("session={}", session);
+    let (status, _, body) = common::send(addr, method, path, &cookies, content_type, body).await;
+    (status, body)
+}
+
+async fn get(addr: std::net::SocketAddr, path: &str, session: &str) -> String {
+    send(addr, "GET", path, session, "text/plain", "").await.1
+}
+
+async fn submit(addr: std::net::SocketAddr, session: &str, history: &str) -> (u16, String) {
+    let body = format!(r#"{{"name": "{}", "history": {}}}"#, session, history);
+    send(addr, "POST", "/api/leaderboard", session, JSON, &body).await
+}
+
+#[tokio::test]
+async fn plays_solves_and_scores_in_memory() {
+    let addr = spawn_app().await;
+    send(addr, "POST", "/edit/play", "ada", FORM, START).await;
+
+    let moved = get(addr, "/slide?idx=7", "ada").await;
+    assert!(moved.contains("moves: 1 "), "{}", moved);
+    let solving = get(addr, "/rest_iterations", "ada").await;
+    assert!(solving.contains("1 / 2"), "{}", solving);
+    let solved = get(addr, "/slide?idx=8", "ada").await;
+    assert!(
+        solved.contains("solved in 2 moves, optimal was 2"),
+        "{}",
+        solved
+    );
+
+    let bests = get(addr, "/bests", "ada").await;
+    assert!(bests.contains("3x3"), "{}", bests);
+    assert!(get(addr, "/bests", "bob")
+        .await
+        .contains("nothing solved yet"));
+
+    assert_eq!(submit(addr, "ada", "[7, 8]").await.0, 201);
+    let again = submit(addr, "ada", "[7, 8]").await;
+    assert_eq!(again.0, 422);
+    assert!(again.1.contains("already on the leaderboard"));
+
+    // scored just now, so it's on today's board as well as the all-time one
+    let path = "/api/leaderboard?rows=3&cols=3&difficulty=easy&period=daily";
+    let entries: Value = serde_json::from_str(&get(addr, path, "bob").await).unwrap();
+    assert_eq!(entries[0]["name"], "ada");
+    assert_eq!(entries[0]["moves"], 2);
+}
+
+#[tokio::test]
+async fn sessions_keep_their_own_games() {
+    let addr = spawn_app().await;
+    send(addr, "POST", "/edit/play", "ada", FORM, START).await;
+    send(addr, "POST", "/edit/play", "bob", FORM, START).await;
+
+    get(addr, "/slide?idx=7", "ada").await;
+    let bob = get(addr, "/slide?idx=5", "bob").await;
+    assert!(bob.contains("moves: 0 "), "{}", bob);
+    let ada = get(addr, "/hint", "ada").await;
+    assert!(ada.contains("moves: 1 "), "{}", ada);
+}
+
+#[tokio::test]
+async fn runs_solve_jobs_in_memory() {
+    let addr = spawn_app().await;
+    let board = slidin_puzzle::slayin::Board::with_size(vec![1, 2, 3, 4, 5, 6, 0, 7, 8], 3, 3);
+    let body = format!(r#"{{"board": "{}"}}"#, board.code());
+    let (status, queued) = send(addr, "POST", "/api/jobs", "ada", JSON, &body).await;
+    assert_eq!(status, 202);
+    let queued: Value = serde_json::from_str(&queued).unwrap();
+
+    let path = format!("/api/jobs/{}", queued["id"]);
+    for _ in 0..200 {
+        let job: Value = serde_json::from_str(&get(addr, &path, "ada").await).unwrap();
+        if job["status"] == "done" {
+            assert_eq!(job["moves"], 2);
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("the job never finished");
+}
+
+// the account cookie a sign up hands back, sent as "session=..; account=.."
+async fn sign_up(addr: std::net::SocketAddr, session: &str, form: &str) -> String {
+    let cookies = format!("session={}", session);
+    let response = common::request(addr, "POST", "/register", &cookies, FORM, form).await;
+    let cookie = response
+        .lines()
+        .find_map(|line| line.strip_prefix("set-cookie: account="))
+        .unwrap();
+    format!("{}; account={}", session, cookie.split(';').next().unwrap())
+}
+
+#[tokio::test]
+async fn merges_a_guest_into_an_account_in_memory() {
+    let addr = spawn_app().await;
+    send(addr, "POST", "/edit/play", "guest", FORM, START).await;
+    get(addr, "/slide?idx=7", "guest").await;
+    get(addr, "/slide?idx=8", "guest").await;
+
+    let signed_in = sign_up(addr, "guest", "name=ada&password=correct+horse&keep=on").await;
+    let profile = get(addr, "/u/ada", &signed_in).await;
+    assert!(profile.contains("1 solved"), "{}", profile);
+    assert_eq!(submit(addr, &signed_in, "[7, 8]").await.0, 201);
+    let bests = get(addr, "/bests", "guest").await;
+    assert!(bests.contains("nothing solved yet"), "{}", bests);
+}
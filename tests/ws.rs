@@ -0,0 +1,175 @@
+mod common;
+
+use common::{recv, send_json, spawn_app, Socket, FORM};
+use serde_json::{json, Value};
+
+// every test gets its own server, the socket on it a fresh session
+async fn connect(name: &str) -> Socket {
+    common::connect(spawn_app(name).await, "/ws", "").await
+}
+
+fn cells(message: &Value) -> Vec<i64> {
+    message["cells"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|cell| cell.as_i64().unwrap())
+        .collect()
+}
+
+// any tile right next to the blank
+fn movable_tile(cells: &[i64]) -> usize {
+    let zero = cells.iter().position(|&cell| cell == 0).unwrap();
+    if zero % 3 > 0 {
+        zero - 1
+    } else {
+        zero + 1
+    }
+}
+
+#[tokio::test]
+async fn sends_the_board_on_connect() {
+    let mut socket = connect("connect").await;
+
+    let board = recv(&mut socket).await;
+    assert_eq!(board["type"], "board");
+    assert_eq!(board["moves"], 0);
+    assert_eq!(board["distance"], board["start_distance"]);
+    assert!(board["feedback"].is_null());
+
+    let mut sorted = cells(&board);
+    sorted.sort();
+    assert_eq!(sorted, (0..9).collect::<Vec<i64>>());
+}
+
+#[tokio::test]
+async fn move_slides_the_tile_and_scores_it() {
+    let mut socket = connect("move").await;
+    let before = cells(&recv(&mut socket).await);
+    let idx = movable_tile(&before);
+
+    send_json(&mut socket, json!({ "type": "move", "idx": idx })).await;
+    let board = recv(&mut socket).await;
+
+    let after = cells(&board);
+    let zero = before.iter().position(|&cell| cell == 0).unwrap();
+    assert_eq!(after[zero], before[idx]);
+    assert_eq!(after[idx], 0);
+    assert_eq!(board["moves"], 1);
+    assert!(["closer", "same", "away"].contains(&board["feedback"].as_str().unwrap()));
+}
+
+#[tokio::test]
+async fn move_away_from_the_blank_is_rejected() {
+    let mut socket = connect("bad-move").await;
+    let before = cells(&recv(&mut socket).await);
+    // a tile in neither the blank's row nor its column can't be pushed
+    let zero = before.iter().position(|&cell| cell == 0).unwrap();
+    let idx = (zero / 3 + 1) % 3 * 3 + (zero % 3 + 1) % 3;
+
+    send_json(&mut socket, json!({ "type": "move", "idx": idx })).await;
+    let error = recv(&mut socket).await;
+    assert_eq!(error["type"], "error");
+
+    send_json(&mut socket, json!({ "type": "move", "idx": 42 })).await;
+    assert_eq!(recv(&mut socket).await["type"], "error");
+}
+
+#[tokio::test]
+async fn following_the_hint_gets_closer() {
+    let mut socket = connect("hint").await;
+    let board = recv(&mut socket).await;
+
+    send_json(&mut socket, json!({ "type": "hint" })).await;
+    let hint = recv(&mut socket).await;
+    assert_eq!(hint["type"], "hint");
+    assert_eq!(hint["distance"], board["distance"]);
+
+    if board["distance"] == 0 {
+        assert!(hint["idx"].is_null());
+        return;
+    }
+
+    send_json(&mut socket, json!({ "type": "move", "idx": hint["idx"] })).await;
+    let board = recv(&mut socket).await;
+    assert_eq!(board["feedback"], "closer");
+    assert_eq!(
+        board["distance"].as_i64().unwrap(),
+        hint["distance"].as_i64().unwrap() - 1
+    );
+}
+
+#[tokio::test]
+async fn solve_reports_progress_then_the_solution() {
+    let mut socket = connect("solve").await;
+    let board = recv(&mut socket).await;
+
+    send_json(&mut socket, json!({ "type": "solve" })).await;
+    assert_eq!(recv(&mut socket).await["type"], "solving");
+
+    // a board count every so often, and the total the search ended on
+    let mut looked_at = Vec::new();
+    let solution = loop {
+        let message = recv(&mut socket).await;
+        match message["type"].as_str().unwrap() {
+            "progress" => looked_at.push(message["nodes"].as_u64().unwrap()),
+            _ => break message,
+        }
+    };
+    assert!(!looked_at.is_empty());
+    assert!(looked_at.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(solution["type"], "solution");
+    let steps = solution["steps"].as_array().unwrap();
+    assert_eq!(steps.first().unwrap(), &board["cells"]);
+    assert_eq!(steps.last().unwrap(), &json!([1, 2, 3, 4, 5, 6, 7, 8, 0]));
+    assert_eq!(steps.len() as i64 - 1, board["distance"].as_i64().unwrap());
+}
+
+// 8 7 6 / 5 4 3 / 2 1 _, played over http first so the socket picks it up.
+// the solver looks at over 10,000 boards on the way
+#[tokio::test]
+async fn long_solves_report_as_they_go() {
+    let addr = spawn_app("long-solve").await;
+    let form = "rows=3&cols=3&cell=8&cell=7&cell=6&cell=5&cell=4&cell=3&cell=2&cell=1&cell=";
+    common::request(addr, "POST", "/edit/play", "session=far", FORM, form).await;
+
+    let mut socket = common::connect(addr, "/ws", "session=far").await;
+    assert_eq!(cells(&recv(&mut socket).await), [8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+    send_json(&mut socket, json!({ "type": "solve" })).await;
+    assert_eq!(recv(&mut socket).await["type"], "solving");
+    let first = recv(&mut socket).await;
+    assert_eq!(first["type"], "progress");
+    assert_eq!(first["nodes"], 10_000);
+}
+
+#[tokio::test]
+async fn new_game_resets_the_counters() {
+    let mut socket = connect("new-game").await;
+    let before = cells(&recv(&mut socket).await);
+
+    send_json(
+        &mut socket,
+        json!({ "type": "move", "idx": movable_tile(&before) }),
+    )
+    .await;
+    assert_eq!(recv(&mut socket).await["moves"], 1);
+
+    send_json(&mut socket, json!({ "type": "new_game" })).await;
+    let board = recv(&mut socket).await;
+    assert_eq!(board["type"], "board");
+    assert_eq!(board["moves"], 0);
+}
+
+#[tokio::test]
+async fn garbage_gets_an_error_back() {
+    let mut socket = connect("garbage").await;
+    recv(&mut socket).await;
+
+    send_json(&mut socket, json!({ "type": "teleport" })).await;
+    assert_eq!(recv(&mut socket).await["type"], "error");
+
+    // the session survives it
+    send_json(&mut socket, json!({ "type": "hint" })).await;
+    assert_eq!(recv(&mut socket).await["type"], "hint");
+}